@@ -14,9 +14,22 @@ use std::fmt::Debug;
 use std::ops::RangeInclusive;
 use std::path::PathBuf;
 
+use crate::bookmarks::{Bookmarks, PanelBookmarks};
+use crate::data_source::DataSource;
+use crate::document::Document;
+use crate::editor::Editor;
+use crate::history::{History, PendingEdit};
+use crate::inspector::{Inspector, PanelInspector};
+use crate::literals::Literals;
+use crate::minimap::Minimap;
+use crate::offsets::OffsetFormat;
+use crate::palette::ByteColors;
 use crate::panels::{FileInfo, FileInfoData};
 use crate::selection::{PanelSelection, Selection};
+use crate::split_view::SplitView;
+use crate::text_decoder::{Glyph, TextDecoder};
 use crate::windows::WindowsData;
+use crate::words::WordFormat;
 
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
@@ -24,7 +37,7 @@ use crate::windows::WindowsData;
 pub struct WombatApp {
     /// Binary file data
     #[serde(skip)]
-    pub(crate) binary_file: Vec<u8>,
+    pub(crate) binary_file: DataSource,
 
     /// Filename of the file
     #[serde(skip)]
@@ -35,6 +48,19 @@ pub struct WombatApp {
     /// Selection
     pub(crate) selection: Selection,
 
+    /// Bookmarks of every file
+    pub(crate) bookmarks: Bookmarks,
+
+    /// Key of the bookmarks of the file
+    #[serde(skip)]
+    pub(crate) bookmark_key: Option<String>,
+
+    /// Data inspector
+    pub(crate) inspector: Inspector,
+
+    /// Settings of the exported literals
+    pub(crate) literals: Literals,
+
     /// File info
     #[serde(skip)]
     pub(crate) file_format: Option<FileInfoData>,
@@ -42,6 +68,34 @@ pub struct WombatApp {
     /// Windows
     #[serde(skip)]
     pub(crate) windows_data: WindowsData,
+
+    /// Edit history
+    #[serde(skip)]
+    pub(crate) history: History,
+
+    /// Byte editor
+    #[serde(skip)]
+    pub(crate) editor: Editor,
+
+    /// Buffer has unsaved changes
+    #[serde(skip)]
+    pub(crate) dirty: bool,
+
+    /// Opened documents, the slot of the active one holds a placeholder
+    #[serde(skip)]
+    pub(crate) documents: Vec<Document>,
+
+    /// Index of the active document
+    #[serde(skip)]
+    pub(crate) active_document: usize,
+
+    /// The active document is closed once its unsaved changes are handled
+    #[serde(skip)]
+    pub(crate) closing_document: bool,
+
+    /// Edit waiting for the active document to be loaded in memory
+    #[serde(skip)]
+    pub(crate) pending_edit: Option<PendingEdit>,
 }
 
 /// default file (wombat icon)
@@ -51,23 +105,53 @@ impl Default for WombatApp {
     fn default() -> Self {
         let File { data, path } = Self::load_default_file();
         Self {
-            binary_file: data,
+            binary_file: DataSource::from(data),
             filename: path,
             display_settings: DisplaySettings::default(),
             selection: Selection::default(),
+            bookmarks: Bookmarks::default(),
+            bookmark_key: None,
+            inspector: Inspector::default(),
+            literals: Literals::default(),
             file_format: None,
             windows_data: WindowsData::new(),
+            history: History::default(),
+            editor: Editor::default(),
+            dirty: false,
+            documents: vec![Document::default()],
+            active_document: 0,
+            closing_document: false,
+            pending_edit: None,
         }
     }
 }
 
 /// Display setting
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
 pub(crate) struct DisplaySettings {
     /// Little endian
     pub(crate) display_lsb: bool,
     /// Bytes per line
     pub(crate) bytes_per_line: usize,
+    /// Byte class colors
+    pub(crate) byte_colors: ByteColors,
+    /// Decoder of the text column
+    pub(crate) text_decoder: TextDecoder,
+    /// Bytes per group of the hex column
+    pub(crate) group_size: usize,
+    /// Groups are shown in little endian order
+    pub(crate) little_endian: bool,
+    /// Value shown for each group
+    pub(crate) word_format: WordFormat,
+    /// Offset column
+    pub(crate) offset_format: OffsetFormat,
+    /// Show each byte as 8 bits
+    pub(crate) bit_view: bool,
+    /// Overview of the file
+    pub(crate) minimap: Minimap,
+    /// Second view of the buffer
+    pub(crate) split_view: SplitView,
 }
 
 impl Default for DisplaySettings {
@@ -75,10 +159,29 @@ impl Default for DisplaySettings {
         Self {
             display_lsb: false,
             bytes_per_line: 32,
+            byte_colors: ByteColors::default(),
+            text_decoder: TextDecoder::Ascii,
+            group_size: 1,
+            little_endian: false,
+            word_format: WordFormat::Hex,
+            offset_format: OffsetFormat::default(),
+            bit_view: false,
+            minimap: Minimap::default(),
+            split_view: SplitView::default(),
         }
     }
 }
 
+impl DisplaySettings {
+    /// Round the bytes per line up to whole groups
+    pub(crate) fn align_bytes_per_line(&mut self) {
+        self.bytes_per_line = self
+            .bytes_per_line
+            .max(1)
+            .next_multiple_of(self.group_size.max(1));
+    }
+}
+
 /// Accent type
 #[derive(Debug, PartialEq)]
 pub(crate) enum Accent {
@@ -92,6 +195,8 @@ pub(crate) enum Accent {
     Binary,
     /// ascii
     Ascii,
+    /// decoded character
+    Decoded,
 }
 
 impl WombatApp {
@@ -102,7 +207,9 @@ impl WombatApp {
         // This is also where you can customize the look and feel of egui using
         // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
         egui_extras::install_image_loaders(&cc.egui_ctx);
-        saved_state
+        let mut app = saved_state;
+        app.display_settings.align_bytes_per_line();
+        app
     }
 
     /// Load the default file (wombat icon)
@@ -156,8 +263,14 @@ impl WombatApp {
         }
     }
 
-    /// Ui for the table representation of a u8
-    pub(crate) fn ui_table_u8(ui: &mut egui::Ui, current: u8, accent_ui: &Accent) {
+    /// Ui for the table representation of a u8, with the character decoded by the text column
+    pub(crate) fn ui_table_u8(
+        ui: &mut egui::Ui,
+        current: u8,
+        accent_ui: &Accent,
+        decoded: Option<(TextDecoder, Glyph)>,
+    ) {
+        let decoded = decoded.filter(|(decoder, _)| *decoder != TextDecoder::Ascii);
         TableBuilder::new(ui)
             .column(Column::auto().resizable(true))
             .column(Column::remainder())
@@ -169,6 +282,9 @@ impl WombatApp {
                         ui.label("octal");
                         ui.label("bin");
                         ui.label("ASCII");
+                        if let Some((decoder, _)) = decoded {
+                            ui.label(decoder.name());
+                        }
                     });
                     row.col(|ui| {
                         let accent = if ui.ctx().theme() == Theme::Light {
@@ -191,6 +307,9 @@ impl WombatApp {
                         accent_label(ui, Accent::Binary, format!("0b{current:08b}"));
                         let ascii_char = WombatApp::ascii_to_string(current);
                         accent_label(ui, Accent::Ascii, ascii_char);
+                        if let Some((_, glyph)) = decoded {
+                            accent_label(ui, Accent::Decoded, glyph.describe());
+                        }
                     });
                 });
             });
@@ -200,12 +319,18 @@ impl WombatApp {
     pub(crate) fn stale(&mut self) {
         self.file_format = None;
         self.windows_data.reset();
+        self.display_settings.minimap.reset();
     }
 }
 
 impl BladvakApp<'_> for WombatApp {
     fn panel_list(&self) -> Vec<Box<dyn BladvakPanel<App = WombatApp>>> {
-        vec![Box::new(FileInfo), Box::new(PanelSelection)]
+        vec![
+            Box::new(FileInfo),
+            Box::new(PanelSelection),
+            Box::new(PanelInspector),
+            Box::new(PanelBookmarks),
+        ]
     }
 
     fn side_panel(&mut self, ui: &mut egui::Ui, func_ui: impl FnOnce(&mut egui::Ui, &mut Self)) {
@@ -219,25 +344,12 @@ impl BladvakApp<'_> for WombatApp {
     }
 
     fn is_open_button(&self) -> bool {
-        true
+        // the native dialog of the framework reads the whole file, see `open_dialog`
+        !is_native()
     }
 
     fn handle_file(&mut self, file: File) -> Result<(), AppError> {
-        self.binary_file = file.data;
-        let file_len = self.binary_file.len();
-        self.filename = file.path;
-        self.stale();
-
-        if self.binary_file.is_empty() {
-            self.selection.reset();
-        } else if let Some((select1, select2)) = self.selection.range.as_mut() {
-            if *select1 > file_len {
-                *select1 = file_len - 1;
-            }
-            if *select2 > file_len {
-                *select2 = file_len - 1;
-            }
-        }
+        self.open_document(DataSource::from(file.data), file.path);
         Ok(())
     }
 
@@ -246,14 +358,43 @@ impl BladvakApp<'_> for WombatApp {
             self.windows_data.ui_top_bar(ui);
         });
         ui.separator();
-        ui.label(format!("File: {}", self.filename.display()));
+        let mode_name = self.editor.mode_name();
+        ui.toggle_value(&mut self.editor.insert_mode, mode_name)
+            .on_hover_text("Insert or overwrite bytes (Insert key)");
+        ui.toggle_value(&mut self.display_settings.split_view.enabled, "Split")
+            .on_hover_text("Show two views of the file");
+        ui.separator();
+        self.ui_tabs(ui);
     }
 
-    fn menu_file(&mut self, _ui: &mut egui::Ui, _error_manager: &mut ErrorManager) {}
+    fn menu_file(&mut self, ui: &mut egui::Ui, error_manager: &mut ErrorManager) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui.button("Open").clicked() {
+            ui.close();
+            if let Err(err) = self.open_dialog() {
+                error_manager.add_error(err);
+            }
+        }
+        if ui.button("Save").clicked()
+            && let Err(err) = self.save()
+        {
+            error_manager.add_error(err);
+        }
+        if ui.button("Save As").clicked()
+            && let Err(err) = self.save_as()
+        {
+            error_manager.add_error(err);
+        }
+    }
 
     fn central_panel(&mut self, ui: &mut egui::Ui, error_manager: &mut ErrorManager) {
+        #[cfg(not(target_arch = "wasm32"))]
+        self.handle_dropped_paths(ui.ctx(), error_manager);
+        self.handle_history_shortcuts(ui.ctx(), error_manager);
         self.app_central_panel(ui, error_manager);
         self.ui_windows(ui, error_manager);
+        self.ui_unsaved_changes(ui, error_manager);
+        self.ui_load_confirmation(ui, error_manager);
     }
 
     fn name() -> String {
@@ -278,11 +419,11 @@ impl BladvakApp<'_> for WombatApp {
         args: &[String],
     ) -> Result<Self, AppError> {
         if is_native() && args.len() > 1 {
-            let path = &args[1];
-            let bytes = std::fs::read(path)?;
+            let path = PathBuf::from(&args[1]);
+            let data = DataSource::open(&path)?;
             let mut app = Self::new_app(saved_state, cc);
-            app.binary_file = bytes;
-            app.filename = PathBuf::from(path);
+            app.binary_file = data;
+            app.filename = path;
             Ok(app)
         } else {
             Ok(Self::new_app(saved_state, cc))
@@ -0,0 +1,108 @@
+//! Bit fields
+
+/// Read `bit_len` bits (at most 64) starting at bit `bit_offset`
+///
+/// MSB-first numbers the bits of a byte from the most significant one and the first bit
+/// is the most significant bit of the value, LSB-first numbers them from the least
+/// significant one and the first bit is the least significant bit of the value.
+pub(crate) fn read_bits(
+    data: &[u8],
+    bit_offset: usize,
+    bit_len: usize,
+    lsb_first: bool,
+) -> Option<u64> {
+    if bit_len == 0 || bit_len > 64 {
+        return None;
+    }
+    let end = bit_offset.checked_add(bit_len)?;
+    if end.div_ceil(8) > data.len() {
+        return None;
+    }
+    let mut value = 0u64;
+    for idx in 0..bit_len {
+        let position = bit_offset + idx;
+        let byte = data[position / 8];
+        if lsb_first {
+            let bit = (byte >> (position % 8)) & 1;
+            value |= u64::from(bit) << idx;
+        } else {
+            let bit = (byte >> (7 - position % 8)) & 1;
+            value = (value << 1) | u64::from(bit);
+        }
+    }
+    Some(value)
+}
+
+/// Read `bit_len` bits starting at bit `bit_offset` of the bit view, where the bits of each
+/// byte are shown reversed when `display_lsb` is set
+pub(crate) fn read_shown_bits(
+    data: &[u8],
+    bit_offset: usize,
+    bit_len: usize,
+    lsb_first: bool,
+    display_lsb: bool,
+) -> Option<u64> {
+    if !display_lsb {
+        return read_bits(data, bit_offset, bit_len, lsb_first);
+    }
+    // shown bit `i` of a byte is raw bit `7 - i`, read the bytes as they are shown
+    let start = bit_offset / 8;
+    let end = bit_offset.checked_add(bit_len)?.div_ceil(8).min(data.len());
+    let shown = data
+        .get(start..end)?
+        .iter()
+        .map(|b| b.reverse_bits())
+        .collect::<Vec<_>>();
+    read_bits(&shown, bit_offset % 8, bit_len, lsb_first)
+}
+
+/// Sign extend a value of `bit_len` bits (1 to 64)
+pub(crate) fn sign_extend(value: u64, bit_len: usize) -> i64 {
+    let shift = 64 - bit_len.clamp(1, 64);
+    (value << shift).cast_signed() >> shift
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_bits, read_shown_bits, sign_extend};
+
+    #[test]
+    fn test_read_bits_msb_first() {
+        let data = [0b1010_1100, 0b0101_0000];
+        assert_eq!(read_bits(&data, 0, 4, false), Some(0b1010));
+        assert_eq!(read_bits(&data, 4, 6, false), Some(0b11_0001));
+        assert_eq!(read_bits(&data, 0, 16, false), Some(0xAC50));
+        assert_eq!(read_bits(&data, 10, 7, false), None);
+        assert_eq!(read_bits(&data, 0, 0, false), None);
+    }
+
+    #[test]
+    fn test_read_bits_lsb_first() {
+        let data = [0b1010_1100, 0b0101_0011];
+        assert_eq!(read_bits(&data, 0, 4, true), Some(0b1100));
+        assert_eq!(read_bits(&data, 6, 4, true), Some(0b1110));
+        assert_eq!(read_bits(&data, 0, 16, true), Some(0x53AC));
+    }
+
+    #[test]
+    fn test_read_shown_bits_lsb_display() {
+        let data = [0b1010_1100, 0b0101_0011];
+        // shown as 00110101 11001010
+        assert_eq!(read_shown_bits(&data, 0, 4, false, true), Some(0b0011));
+        assert_eq!(read_shown_bits(&data, 6, 4, false, true), Some(0b0111));
+        assert_eq!(read_shown_bits(&data, 0, 4, true, true), Some(0b0101));
+        assert_eq!(
+            read_shown_bits(&data, 0, 16, false, false),
+            read_bits(&data, 0, 16, false)
+        );
+        assert_eq!(read_shown_bits(&data, 10, 7, false, true), None);
+    }
+
+    #[test]
+    fn test_sign_extend() {
+        assert_eq!(sign_extend(0b111, 3), -1);
+        assert_eq!(sign_extend(0b011, 3), 3);
+        assert_eq!(sign_extend(0x8000, 16), -32768);
+        assert_eq!(sign_extend(u64::MAX, 64), -1);
+    }
+}
@@ -0,0 +1,431 @@
+//! Bookmarks
+
+use bladvak::{
+    ErrorManager,
+    app::BladvakPanel,
+    eframe::egui::{self, Color32, RichText, TextEdit},
+};
+use std::{collections::HashMap, path::Path, path::PathBuf};
+
+use crate::WombatApp;
+use crate::history::Edit;
+
+/// Named range of bytes with a note
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct Bookmark {
+    /// first byte
+    pub(crate) start: usize,
+    /// last byte
+    pub(crate) end: usize,
+    /// name
+    pub(crate) name: String,
+    /// free-text note
+    #[serde(default)]
+    pub(crate) note: String,
+    /// color of the range
+    pub(crate) color: Color32,
+}
+
+impl Bookmark {
+    /// Colors given to new bookmarks
+    const COLORS: [Color32; 5] = [
+        Color32::from_rgb(230, 80, 80),
+        Color32::from_rgb(80, 160, 230),
+        Color32::from_rgb(90, 190, 90),
+        Color32::from_rgb(200, 120, 220),
+        Color32::from_rgb(230, 170, 50),
+    ];
+
+    /// Bookmark `start..=end`, numbered `number`
+    fn new(start: usize, end: usize, number: usize) -> Self {
+        Self {
+            start,
+            end,
+            name: format!("Bookmark {}", number + 1),
+            note: String::new(),
+            color: Self::COLORS[number % Self::COLORS.len()],
+        }
+    }
+
+    /// The bookmark holds `offset`
+    pub(crate) fn contains(&self, offset: usize) -> bool {
+        (self.start..=self.end).contains(&offset)
+    }
+
+    /// Move the bookmark after a splice of `removed` bytes replaced by `inserted` bytes at `offset`,
+    /// false if all its bytes were removed
+    fn shift(&mut self, offset: usize, removed: usize, inserted: usize) -> bool {
+        if removed == inserted {
+            // overwritten in place, nothing moves
+            return true;
+        }
+        let removed_end = offset + removed;
+        // none for the bytes of the splice
+        let moved = |idx: usize| {
+            if idx < offset {
+                Some(idx)
+            } else if idx >= removed_end {
+                Some(idx - removed + inserted)
+            } else {
+                None
+            }
+        };
+        match (moved(self.start), moved(self.end)) {
+            (Some(start), Some(end)) => (self.start, self.end) = (start, end),
+            (None, Some(end)) => (self.start, self.end) = (offset, end),
+            // the bookmark starts before the splice, so `offset` is not zero
+            (Some(start), None) => (self.start, self.end) = (start, offset + inserted - 1),
+            (None, None) if inserted > 0 => {
+                (self.start, self.end) = (offset, offset + inserted - 1);
+            }
+            (None, None) => return false,
+        }
+        true
+    }
+
+    /// Does a splice of `removed` bytes replaced by `inserted` bytes at `offset` cut the bookmark,
+    /// so that shifting it back does not restore its range
+    fn is_cut(&self, offset: usize, removed: usize, inserted: usize) -> bool {
+        removed > 0 && removed != inserted && self.start < offset + removed && offset <= self.end
+    }
+}
+
+/// Bookmark cut or removed by an edit, restored when the edit is undone or redone
+#[derive(Debug, Clone)]
+pub(crate) struct BookmarkChange {
+    /// index of the splice, in the order the edit was applied or reverted
+    step: usize,
+    /// index of the bookmark before the splice
+    index: usize,
+    /// bookmark before the splice
+    bookmark: Bookmark,
+    /// the splice removed the bookmark
+    removed: bool,
+}
+
+/// Bookmarks of every file, keyed by file path and content hash
+#[derive(serde::Deserialize, serde::Serialize, Debug, Default)]
+#[serde(default)]
+pub(crate) struct Bookmarks {
+    /// bookmarks of each file
+    files: HashMap<String, Vec<Bookmark>>,
+    /// JSON pasted to be imported
+    #[serde(skip)]
+    import_text: String,
+    /// import error
+    #[serde(skip)]
+    import_error: Option<String>,
+}
+
+impl Bookmarks {
+    /// Bookmarks of the file `key`
+    pub(crate) fn of(&self, key: Option<&str>) -> &[Bookmark] {
+        key.and_then(|key| self.files.get(key))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Move the bookmarks of the file `key` after `edit` is applied, or reverted,
+    /// and restore the bookmarks cut when it was last reverted, or applied
+    pub(crate) fn shift(&mut self, key: Option<&str>, edit: &mut Edit, reverted: bool) {
+        let restored = std::mem::take(&mut edit.bookmarks);
+        let Some(bookmarks) = key.and_then(|key| self.files.get_mut(key)) else {
+            return;
+        };
+        let shifts = edit.shifts(reverted);
+        let steps = shifts.len();
+        for (step, (offset, removed, inserted)) in shifts.into_iter().enumerate() {
+            let mut kept = Vec::with_capacity(bookmarks.len());
+            for (index, mut bookmark) in std::mem::take(bookmarks).into_iter().enumerate() {
+                let before = bookmark
+                    .is_cut(offset, removed, inserted)
+                    .then(|| bookmark.clone());
+                let keep = bookmark.shift(offset, removed, inserted);
+                if let Some(before) = before {
+                    edit.bookmarks.push(BookmarkChange {
+                        step,
+                        index,
+                        bookmark: before,
+                        removed: !keep,
+                    });
+                }
+                if keep {
+                    kept.push(bookmark);
+                }
+            }
+            *bookmarks = kept;
+            // this splice reverts the one applied at the mirrored step
+            for change in restored.iter().filter(|c| c.step + step + 1 == steps) {
+                if change.removed {
+                    let index = change.index.min(bookmarks.len());
+                    bookmarks.insert(index, change.bookmark.clone());
+                } else if let Some(bookmark) = bookmarks.get_mut(change.index) {
+                    bookmark.clone_from(&change.bookmark);
+                }
+            }
+        }
+    }
+
+    /// Move the bookmarks of a file to its new key
+    pub(crate) fn rekey(&mut self, old_key: &str, new_key: &str) {
+        if old_key != new_key
+            && let Some(bookmarks) = self.files.remove(old_key)
+        {
+            self.files.insert(new_key.to_string(), bookmarks);
+        }
+    }
+}
+
+/// Hash of the whole content of a file, read in place so mapped files are not copied
+fn content_hash(data: &[u8]) -> u64 {
+    xxhash_rust::xxh3::xxh3_64(data)
+}
+
+/// Key of the bookmarks of a file
+pub(crate) fn bookmark_key(filename: &Path, data: &[u8]) -> String {
+    format!("{}#{:016x}", filename.display(), content_hash(data))
+}
+
+/// Parse bookmarks exported as JSON
+/// # Errors
+/// Fails if the JSON is not a list of bookmarks
+fn parse_bookmarks(json: &str) -> Result<Vec<Bookmark>, String> {
+    serde_json::from_str(json).map_err(|err| format!("Invalid bookmarks: {err}"))
+}
+
+impl WombatApp {
+    /// Key of the bookmarks of the active document, computed once per document
+    pub(crate) fn ensure_bookmark_key(&mut self) {
+        if self.bookmark_key.is_none() {
+            self.bookmark_key = Some(bookmark_key(&self.filename, &self.binary_file));
+        }
+    }
+
+    /// Bookmarks of the active document
+    pub(crate) fn current_bookmarks(&self) -> &[Bookmark] {
+        self.bookmarks.of(self.bookmark_key.as_deref())
+    }
+
+    /// Keep the bookmarks of the active document once its content is saved
+    pub(crate) fn rekey_bookmarks(&mut self) {
+        let new_key = bookmark_key(&self.filename, &self.binary_file);
+        if let Some(old_key) = self.bookmark_key.replace(new_key.clone()) {
+            self.bookmarks.rekey(&old_key, &new_key);
+        }
+    }
+}
+
+/// Bookmarks panel
+#[derive(Debug)]
+pub(crate) struct PanelBookmarks;
+
+impl PanelBookmarks {
+    /// Show the export and import buttons
+    fn ui_share(
+        bookmarks: &mut Bookmarks,
+        key: &str,
+        ui: &mut egui::Ui,
+        error_manager: &mut ErrorManager,
+    ) {
+        ui.collapsing("Share", |ui| {
+            let current = bookmarks.files.get(key).map_or(&[][..], Vec::as_slice);
+            match serde_json::to_string_pretty(current) {
+                Ok(json) => {
+                    ui.horizontal(|ui| {
+                        if ui.button("Export as JSON").clicked()
+                            && let Err(e) = bladvak::utils::save_file(
+                                json.as_bytes(),
+                                &PathBuf::from("bookmarks.json"),
+                            )
+                        {
+                            error_manager.add_error(e);
+                        }
+                        if ui.button("Copy as JSON").clicked() {
+                            ui.ctx().copy_text(json);
+                        }
+                    });
+                }
+                Err(err) => {
+                    ui.label(RichText::new(err.to_string()).color(Color32::LIGHT_RED));
+                }
+            }
+            ui.label("Import from JSON");
+            if ui
+                .add(TextEdit::multiline(&mut bookmarks.import_text).desired_rows(3))
+                .changed()
+            {
+                bookmarks.import_error = None;
+            }
+            if ui.button("Import").clicked() {
+                match parse_bookmarks(&bookmarks.import_text) {
+                    Ok(imported) => {
+                        bookmarks
+                            .files
+                            .entry(key.to_string())
+                            .or_default()
+                            .extend(imported);
+                        bookmarks.import_text.clear();
+                    }
+                    Err(err) => bookmarks.import_error = Some(err),
+                }
+            }
+            if let Some(err) = &bookmarks.import_error {
+                ui.label(RichText::new(err).color(Color32::LIGHT_RED));
+            }
+        });
+    }
+}
+
+impl BladvakPanel for PanelBookmarks {
+    type App = WombatApp;
+    fn name(&self) -> &'static str {
+        "Bookmarks"
+    }
+    fn has_ui(&self) -> bool {
+        true
+    }
+    fn has_settings(&self) -> bool {
+        false
+    }
+    fn ui(&self, app: &mut WombatApp, ui: &mut egui::Ui, error_manager: &mut ErrorManager) {
+        app.ensure_bookmark_key();
+        let Some(key) = app.bookmark_key.clone() else {
+            return;
+        };
+        let offset_format = &app.display_settings.offset_format;
+        let offset_digits = offset_format.digits(app.binary_file.len());
+        let list = app.bookmarks.files.entry(key.clone()).or_default();
+        if let Some((start, end)) = app.selection.range
+            && ui.button("Bookmark selection").clicked()
+        {
+            list.push(Bookmark::new(start, end, list.len()));
+        }
+        if list.is_empty() {
+            ui.label("No bookmarks");
+        }
+        let mut jump = None;
+        let mut removed = None;
+        for (idx, bookmark) in list.iter_mut().enumerate() {
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.color_edit_button_srgba(&mut bookmark.color);
+                if ui
+                    .link(&bookmark.name)
+                    .on_hover_text("Go to bookmark")
+                    .clicked()
+                {
+                    jump = Some((bookmark.start, bookmark.end));
+                }
+                if ui.small_button("x").on_hover_text("Remove").clicked() {
+                    removed = Some(idx);
+                }
+            });
+            ui.label(format!(
+                "{} -> {}",
+                offset_format.format(bookmark.start, offset_digits),
+                offset_format.format(bookmark.end, offset_digits)
+            ));
+            egui::CollapsingHeader::new("Edit")
+                .id_salt(("bookmark", idx))
+                .show(ui, |ui| {
+                    ui.text_edit_singleline(&mut bookmark.name);
+                    ui.add(
+                        TextEdit::multiline(&mut bookmark.note)
+                            .hint_text("Note")
+                            .desired_rows(2),
+                    );
+                });
+            if !bookmark.note.is_empty() {
+                ui.label(&bookmark.note);
+            }
+        }
+        if let Some(idx) = removed {
+            list.remove(idx);
+        }
+        if list.is_empty() {
+            app.bookmarks.files.remove(&key);
+        }
+        if let Some((start, end)) = jump {
+            app.windows_data.goto.remember(app.selection.position());
+            app.selection.select_range(start, end);
+            app.selection.clamp(app.binary_file.len());
+        }
+        ui.separator();
+        Self::ui_share(&mut app.bookmarks, &key, ui, error_manager);
+    }
+
+    fn ui_settings(
+        &self,
+        _app: &mut WombatApp,
+        _ui: &mut egui::Ui,
+        _error_manager: &mut ErrorManager,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bookmark, Bookmarks, bookmark_key, parse_bookmarks};
+    use crate::history::Edit;
+    use std::path::Path;
+
+    #[test]
+    fn test_bookmark_key() {
+        let key = bookmark_key(Path::new("file.bin"), b"wombat");
+        assert_eq!(key, bookmark_key(Path::new("file.bin"), b"wombat"));
+        assert_ne!(key, bookmark_key(Path::new("file.bin"), b"wombats"));
+        assert_ne!(key, bookmark_key(Path::new("other.bin"), b"wombat"));
+        assert_eq!(bookmark_key(Path::new("a"), b""), "a#2d06800538d394c2");
+        let mut large = vec![0; 200 * 1024];
+        let key = bookmark_key(Path::new("a"), &large);
+        large[100 * 1024] = 1;
+        assert_ne!(key, bookmark_key(Path::new("a"), &large));
+    }
+
+    #[test]
+    fn test_shift() {
+        let mut bookmark = Bookmark::new(4, 7, 0);
+        assert!(bookmark.shift(0, 0, 2));
+        assert_eq!((bookmark.start, bookmark.end), (6, 9));
+        assert!(bookmark.shift(10, 3, 0));
+        assert_eq!((bookmark.start, bookmark.end), (6, 9));
+        assert!(bookmark.shift(4, 4, 0));
+        assert_eq!((bookmark.start, bookmark.end), (4, 5));
+        assert!(bookmark.shift(5, 2, 1));
+        assert_eq!((bookmark.start, bookmark.end), (4, 5));
+        assert!(bookmark.shift(3, 4, 4));
+        assert_eq!((bookmark.start, bookmark.end), (4, 5));
+        assert!(!bookmark.shift(2, 6, 0));
+    }
+
+    #[test]
+    fn test_restore_on_undo() {
+        let data = [0; 16];
+        let bookmarks = vec![
+            Bookmark::new(2, 3, 0),
+            Bookmark::new(4, 9, 1),
+            Bookmark::new(12, 13, 2),
+        ];
+        let mut files = Bookmarks::default();
+        files.files.insert("a".to_string(), bookmarks.clone());
+        let mut edit = Edit::delete_ranges(&data, &[(1, 5), (11, 14)]);
+        files.shift(Some("a"), &mut edit, false);
+        assert_eq!(files.of(Some("a")).len(), 1);
+        assert_eq!(
+            (files.of(Some("a"))[0].start, files.of(Some("a"))[0].end),
+            (1, 4)
+        );
+        files.shift(Some("a"), &mut edit, true);
+        assert_eq!(files.of(Some("a")), bookmarks.as_slice());
+        files.shift(Some("a"), &mut edit, false);
+        files.shift(Some("a"), &mut edit, true);
+        assert_eq!(files.of(Some("a")), bookmarks.as_slice());
+    }
+
+    #[test]
+    fn test_bookmarks_json() {
+        let bookmarks = vec![Bookmark::new(4, 7, 0), Bookmark::new(0, 0, 1)];
+        let json = serde_json::to_string(&bookmarks).unwrap_or_default();
+        assert_eq!(parse_bookmarks(&json), Ok(bookmarks));
+        assert!(parse_bookmarks("{}").is_err());
+    }
+}
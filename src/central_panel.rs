@@ -1,10 +1,18 @@
 //! Central panel
 
-use bladvak::eframe::egui::{self, FontFamily, FontId, ScrollArea, TextStyle, Theme, Vec2};
+use bladvak::eframe::egui::{
+    self, FontFamily, FontId, Key, ScrollArea, Stroke, StrokeKind, TextStyle, Theme, Vec2,
+};
 use bladvak::errors::ErrorManager;
 
 use crate::WombatApp;
 use crate::app::Accent;
+use crate::editor::EditColumn;
+use crate::minimap::Minimap;
+use crate::selection::CursorMove;
+use crate::text_decoder::TextDecoder;
+use crate::windows::Region;
+use crate::words::{HexLayout, WordFormat};
 
 impl WombatApp {
     /// Show the central panel
@@ -14,23 +22,70 @@ impl WombatApp {
     pub(crate) fn app_central_panel(
         &mut self,
         ui: &mut egui::Ui,
-        _error_manager: &mut ErrorManager,
+        error_manager: &mut ErrorManager,
     ) {
-        ScrollArea::vertical().show_viewport(ui, |ui: &mut egui::Ui, viewport: egui::Rect| {
-            // 1) compute text metrics: row height using monospace TextStyle if available
-            let text_style = TextStyle::Monospace;
-            // Choose a monospace font id. Use the style's size for monospace if available:
-            let font_size = ui
-                .style()
-                .text_styles
-                .get(&text_style)
-                .map_or(14.0, |s| s.size);
-            let row_height = ui.text_style_height(&text_style).max(14.0); // fallback
-            // total lines we'll render
-            let lines_total = self
-                .binary_file
-                .len()
-                .div_ceil(self.display_settings.bytes_per_line);
+        self.handle_edit_input(ui.ctx(), error_manager);
+        self.ensure_bookmark_key();
+        // 1) compute text metrics: row height using monospace TextStyle if available
+        let text_style = TextStyle::Monospace;
+        // Choose a monospace font id. Use the style's size for monospace if available:
+        let font_size = ui
+            .style()
+            .text_styles
+            .get(&text_style)
+            .map_or(14.0, |s| s.size);
+        let row_height = ui.text_style_height(&text_style).max(14.0); // fallback
+        if self.display_settings.minimap.enabled {
+            egui::SidePanel::right("minimap")
+                .resizable(false)
+                .exact_width(Minimap::WIDTH)
+                .show_inside(ui, |ui| self.ui_minimap(ui));
+        }
+        if self.display_settings.split_view.enabled {
+            let height = (ui.available_height() - row_height * 2.0) / 2.0;
+            let first = self.hex_view(ui, 0, (font_size, row_height), Some(height));
+            ui.separator();
+            self.display_settings.split_view.ui(ui, row_height);
+            let second = self.hex_view(ui, 1, (font_size, row_height), None);
+            if self.display_settings.split_view.sync([first, second]) {
+                ui.ctx().request_repaint();
+            }
+        } else {
+            self.display_settings.split_view.active = 0;
+            self.hex_view(ui, 0, (font_size, row_height), None);
+        }
+    }
+
+    /// Show a hex view of the buffer, returns its scroll offset
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    #[allow(clippy::cast_precision_loss)]
+    fn hex_view(
+        &mut self,
+        ui: &mut egui::Ui,
+        view: usize,
+        (font_size, row_height): (f32, f32),
+        max_height: Option<f32>,
+    ) -> f32 {
+        let is_active = view == self.display_settings.split_view.active;
+        let mut scroll_area = ScrollArea::vertical().id_salt(("hex_view", view));
+        if let Some(max_height) = max_height {
+            scroll_area = scroll_area
+                .max_height(max_height)
+                .auto_shrink([false, false]);
+        }
+        if let Some(offset) = self.display_settings.split_view.take_pending(view) {
+            scroll_area = scroll_area.vertical_scroll_offset(offset);
+        }
+        if is_active && let Some(offset) = self.display_settings.minimap.jump.take() {
+            let line = offset / self.display_settings.bytes_per_line;
+            scroll_area = scroll_area.vertical_scroll_offset(line as f32 * row_height);
+        }
+        let output = scroll_area.show_viewport(ui, |ui: &mut egui::Ui, viewport: egui::Rect| {
+            // total lines we'll render, including the append position of the cursor
+            let file_len = self.binary_file.len();
+            let nb_cells = file_len + usize::from(self.selection.cursor == Some(file_len));
+            let lines_total = nb_cells.div_ceil(self.display_settings.bytes_per_line);
 
             // total content height in points
             let total_height = (lines_total as f32) * row_height;
@@ -38,6 +93,18 @@ impl WombatApp {
             // Reserve the space for the whole content (so scrollbar knows the full size)
             // We don't actually draw all rows, only the visible ones.
             let _rect = ui.allocate_space(egui::vec2(viewport.width(), total_height));
+            let page_lines = (viewport.height() / row_height).floor().max(1.0) as usize;
+            if is_active {
+                self.handle_cursor_keys(ui.ctx(), page_lines);
+            }
+            if is_active && let Some(scroll_to) = self.selection.scroll_to.take() {
+                let line = scroll_to / self.display_settings.bytes_per_line;
+                let line_rect = egui::Rect::from_min_size(
+                    ui.min_rect().min + Vec2::new(0.0, line as f32 * row_height),
+                    egui::vec2(1.0, row_height),
+                );
+                ui.scroll_to_rect(line_rect, None);
+            }
             // 2) find visible line range from viewport
             // viewport.rect.top() is the y of the top of the visible area in "world coordinates".
             // Convert to a line index
@@ -51,10 +118,26 @@ impl WombatApp {
             // clamp to valid range
             let first_line = first_line.min(lines_total);
             let last_line = last_line.min(lines_total);
+            if is_active {
+                self.display_settings.minimap.view = (
+                    first_line * self.display_settings.bytes_per_line,
+                    (last_line * self.display_settings.bytes_per_line).saturating_sub(1),
+                );
+            }
             // padding from left inside the viewport
             let left = viewport.left() + 4.0;
-            self.show_lines(ui, left, font_size, row_height, (first_line, last_line));
+            self.show_lines(
+                ui,
+                (view, left),
+                font_size,
+                row_height,
+                (first_line, last_line),
+            );
+            if is_active {
+                self.auto_scroll(ui, row_height);
+            }
         });
+        output.state.offset.y
     }
 
     /// Show file lines
@@ -66,7 +149,7 @@ impl WombatApp {
     fn show_lines(
         &mut self,
         ui: &mut egui::Ui,
-        left: f32,
+        (view, left): (usize, f32),
         font_size: f32,
         row_height: f32,
         (first_line, last_line): (usize, usize),
@@ -81,43 +164,110 @@ impl WombatApp {
 
         // we'll draw 3 columns: offset, hex bytes, ascii
         // Choose x positions relative to `left`
-        let offset_col_width = 80.0; // "00000000:" width
+        let char_width = ui.fonts_mut(|f| f.glyph_width(&font_id, '0'));
+        let offset_format = &self.display_settings.offset_format;
+        let offset_digits = offset_format.digits(self.binary_file.len());
+        // digits, ':' and a gap
+        let offset_col_width = (offset_digits + 2) as f32 * char_width;
         let hex_col_x = left + offset_col_width;
-        // For hex column width estimate: bytes_per_line * 3 chars ("xx ") maybe plus small gap
-        let hex_col_width = (bytes_per_line as f32) * 3.0 * (font_size * 0.6); // rough estimate
+        let bit_view = self.display_settings.bit_view;
+        let layout = if bit_view {
+            HexLayout::bits(char_width)
+        } else {
+            HexLayout::new(
+                self.display_settings.group_size,
+                self.display_settings.little_endian,
+                self.display_settings.word_format,
+                char_width,
+            )
+        };
+        let word_format = if bit_view {
+            WordFormat::Hex
+        } else {
+            self.display_settings.word_format
+        };
+        let bit_range = self.selection.bit_range();
+        let group_size = layout.group_size();
+        let hex_col_width = layout.width(bytes_per_line);
         let ascii_col_x = hex_col_x + hex_col_width + 8.0;
+        let is_light = ui.ctx().theme() == Theme::Light;
+        let bookmarks = self.current_bookmarks();
+        let mut clicked = None;
+        let mut clicked_bit = None;
+        let mut drag_started = None;
+        let mut drag_over = None;
         for line in first_line..last_line {
             let offset = line * bytes_per_line;
             let slice_end = (offset + bytes_per_line).min(self.binary_file.len());
             let slice = &self.binary_file[offset..slice_end];
 
             // formatted offset
-            let offset_text = format!("{offset:08X}:");
+            let offset_text = format!("{}:", offset_format.format(offset, offset_digits));
 
-            // hex text: group each byte as two hex digits separated by a space
-            let mut hex_buf = Vec::with_capacity(bytes_per_line);
-            for b in slice {
-                if self.display_settings.display_lsb {
-                    hex_buf.push(format!("{:02X} ", b.reverse_bits()));
-                } else {
-                    hex_buf.push(format!("{b:02X} "));
+            // hex text: each byte as two hex digits, placed in its group by the layout
+            let shown_bytes = slice
+                .iter()
+                .map(|b| {
+                    if self.display_settings.display_lsb {
+                        b.reverse_bits()
+                    } else {
+                        *b
+                    }
+                })
+                .collect::<Vec<_>>();
+            let hex_buf = shown_bytes
+                .iter()
+                .map(|b| {
+                    if bit_view {
+                        format!("{b:08b}")
+                    } else {
+                        format!("{b:02X}")
+                    }
+                })
+                .collect::<Vec<_>>();
+            // groups shown as a value instead of hex digits
+            let words = shown_bytes
+                .chunks(group_size)
+                .map(|group| word_format.format(group, self.display_settings.little_endian))
+                .collect::<Vec<_>>();
+
+            // text: decoded with the bytes around the line, for characters spanning lines
+            let decoder = self.display_settings.text_decoder;
+            let context_start = offset.saturating_sub(decoder.context()) & !1;
+            let context_end = (slice_end + 4).min(self.binary_file.len());
+            let mut context = self.binary_file[context_start..context_end].to_vec();
+            if self.display_settings.display_lsb {
+                for b in &mut context {
+                    *b = b.reverse_bits();
                 }
             }
+            let glyphs = (offset..slice_end)
+                .map(|idx| decoder.decode_at(&context, idx - context_start))
+                .collect::<Vec<_>>();
+            let ascii_buf = glyphs
+                .iter()
+                .map(|glyph| glyph.display())
+                .collect::<Vec<_>>();
 
-            // ascii text: printable ascii or '.'
-            let mut ascii_buf = Vec::with_capacity(bytes_per_line);
-            for b in slice {
-                let b = if self.display_settings.display_lsb {
-                    b.reverse_bits()
-                } else {
-                    *b
-                };
-                let c = match b {
-                    x if Self::RANGE_ASCII_PRINTABLE.contains(&x) => x as char,
-                    _ => '.',
-                };
-                ascii_buf.push(c);
-            }
+            // innermost detected structure of each byte
+            let line_regions = self
+                .windows_data
+                .detection
+                .regions_in(offset..=offset + bytes_per_line - 1)
+                .collect::<Vec<_>>();
+            let regions = (offset..slice_end)
+                .map(|idx| {
+                    line_regions
+                        .iter()
+                        .filter(|(_, region)| region.range.contains(&idx))
+                        .min_by_key(|(_, region)| region.range.end() - region.range.start())
+                        .copied()
+                })
+                .collect::<Vec<_>>();
+            // last added bookmark of each byte
+            let byte_bookmarks = (offset..slice_end)
+                .map(|idx| bookmarks.iter().rfind(|bookmark| bookmark.contains(idx)))
+                .collect::<Vec<_>>();
 
             // draw using painter at explicit positions so alignment stays correct
             let origin = ui.min_rect().min;
@@ -128,29 +278,159 @@ impl WombatApp {
                 font_id.clone(),
                 ui.visuals().text_color(),
             );
-            for (idx, (hex, ascii)) in std::iter::zip(&hex_buf, &ascii_buf).enumerate() {
-                let x_pos = (idx as f32) * 3.0 * (font_size * 0.6);
-                let color = if self
-                    .selection
-                    .range
-                    .is_some_and(|s| (s.0..=s.1).contains(&(offset + idx)))
-                {
-                    if ui.ctx().theme() == Theme::Light {
+            for (idx, ((hex, ascii), b)) in
+                std::iter::zip(std::iter::zip(&hex_buf, &ascii_buf), &shown_bytes).enumerate()
+            {
+                let x_pos = layout.byte_x(idx, slice.len());
+                let color = if self.selection.contains(offset + idx) {
+                    if is_light {
                         self.selection.color.0
                     } else {
                         self.selection.color.1
                     }
+                } else if self.history.is_modified(offset + idx) {
+                    if is_light {
+                        self.editor.modified_color.0
+                    } else {
+                        self.editor.modified_color.1
+                    }
                 } else {
-                    ui.visuals().text_color()
+                    self.display_settings
+                        .byte_colors
+                        .color(*b, is_light)
+                        .unwrap_or_else(|| ui.visuals().text_color())
+                };
+                let pending_hex;
+                let hex = match self.editor.pending_nibble {
+                    Some(high) if self.selection.cursor == Some(offset + idx) => {
+                        pending_hex = format!("{high:X}_");
+                        &pending_hex
+                    }
+                    _ => hex,
                 };
-                painter.text(
-                    origin + Vec2::new(hex_col_x + x_pos, y),
-                    egui::Align2::LEFT_TOP,
-                    hex,
-                    font_id.clone(),
-                    color,
-                );
                 let hex_pos = (idx as f32) * (font_size * 0.6);
+                if let Some((region_idx, _)) = regions[idx] {
+                    // the band covers the gap to the next byte of the same structure
+                    let next_x = layout.byte_x(idx + 1, slice.len());
+                    let same_next = regions.get(idx + 1).is_some_and(|next| {
+                        next.is_some_and(|(next_idx, _)| next_idx == region_idx)
+                    });
+                    let band_width = if same_next && next_x > x_pos {
+                        next_x - x_pos
+                    } else {
+                        layout.byte_width()
+                    };
+                    let region_color = Region::color(region_idx, is_light);
+                    painter.rect_filled(
+                        egui::Rect::from_min_size(
+                            origin + Vec2::new(hex_col_x + x_pos, y),
+                            egui::vec2(band_width, row_height),
+                        ),
+                        0.0,
+                        region_color,
+                    );
+                    painter.rect_filled(
+                        egui::Rect::from_min_size(
+                            origin + Vec2::new(ascii_col_x + hex_pos, y),
+                            egui::vec2(font_size * 0.6, row_height),
+                        ),
+                        0.0,
+                        region_color,
+                    );
+                }
+                if let Some(bookmark) = byte_bookmarks[idx] {
+                    let bookmark_color = bookmark.color.gamma_multiply(0.35);
+                    painter.rect_filled(
+                        egui::Rect::from_min_size(
+                            origin + Vec2::new(hex_col_x + x_pos, y),
+                            egui::vec2(layout.byte_width(), row_height),
+                        ),
+                        0.0,
+                        bookmark_color,
+                    );
+                    painter.rect_filled(
+                        egui::Rect::from_min_size(
+                            origin + Vec2::new(ascii_col_x + hex_pos, y),
+                            egui::vec2(char_width, row_height),
+                        ),
+                        0.0,
+                        bookmark_color,
+                    );
+                }
+                if self.windows_data.search.is_match(offset + idx) {
+                    let search_color = if is_light {
+                        self.windows_data.search.color.0
+                    } else {
+                        self.windows_data.search.color.1
+                    };
+                    painter.rect_filled(
+                        egui::Rect::from_min_size(
+                            origin + Vec2::new(hex_col_x + x_pos, y),
+                            egui::vec2(layout.byte_width(), row_height),
+                        ),
+                        0.0,
+                        search_color,
+                    );
+                    painter.rect_filled(
+                        egui::Rect::from_min_size(
+                            origin + Vec2::new(ascii_col_x + hex_pos, y),
+                            egui::vec2(char_width, row_height),
+                        ),
+                        0.0,
+                        search_color,
+                    );
+                }
+                if let Some((start, end)) = bit_range {
+                    // selected bits of the byte, as displayed
+                    let first_bit = (offset + idx) * 8;
+                    let from = start.max(first_bit);
+                    let to = end.min(first_bit + 7);
+                    if from <= to {
+                        let selection_color = if is_light {
+                            self.selection.color.0
+                        } else {
+                            self.selection.color.1
+                        };
+                        painter.rect_filled(
+                            egui::Rect::from_min_size(
+                                origin
+                                    + Vec2::new(
+                                        hex_col_x + x_pos + (from - first_bit) as f32 * char_width,
+                                        y,
+                                    ),
+                                egui::vec2((to - from + 1) as f32 * char_width, row_height),
+                            ),
+                            0.0,
+                            selection_color.gamma_multiply(0.35),
+                        );
+                    }
+                }
+                match &words[idx / group_size] {
+                    Some(word) if idx % group_size == 0 => {
+                        let group = idx / group_size;
+                        painter.text(
+                            origin
+                                + Vec2::new(
+                                    hex_col_x + layout.group_x(group) + layout.group_width(),
+                                    y,
+                                ),
+                            egui::Align2::RIGHT_TOP,
+                            word,
+                            font_id.clone(),
+                            color,
+                        );
+                    }
+                    Some(_) => {}
+                    None => {
+                        painter.text(
+                            origin + Vec2::new(hex_col_x + x_pos, y),
+                            egui::Align2::LEFT_TOP,
+                            hex,
+                            font_id.clone(),
+                            color,
+                        );
+                    }
+                }
                 painter.text(
                     origin + Vec2::new(ascii_col_x + hex_pos, y),
                     egui::Align2::LEFT_TOP,
@@ -160,21 +440,53 @@ impl WombatApp {
                 );
             }
 
-            let char_width = ui.fonts_mut(|f| f.glyph_width(&font_id, '0'));
-            let hex_group_width = char_width * 2.0; // "FF " is 3 chars
+            // edit cursor
+            if let Some(cursor) = self.selection.cursor
+                && (offset..offset + bytes_per_line).contains(&cursor)
+            {
+                let idx = cursor - offset;
+                let hex_rect = egui::Rect::from_min_size(
+                    origin + Vec2::new(hex_col_x + layout.byte_x(idx, slice.len()), y),
+                    egui::vec2(layout.byte_width(), row_height),
+                );
+                let ascii_rect = egui::Rect::from_min_size(
+                    origin + Vec2::new(ascii_col_x + (idx as f32) * (font_size * 0.6), y),
+                    egui::vec2(char_width, row_height),
+                );
+                let (active_rect, other_rect) = match self.editor.column {
+                    EditColumn::Hex => (hex_rect, ascii_rect),
+                    EditColumn::Ascii => (ascii_rect, hex_rect),
+                };
+                let cursor_color = ui.visuals().strong_text_color();
+                painter.rect_stroke(
+                    active_rect,
+                    1.0,
+                    Stroke::new(1.5, cursor_color),
+                    StrokeKind::Outside,
+                );
+                painter.rect_stroke(
+                    other_rect,
+                    1.0,
+                    Stroke::new(0.5, cursor_color),
+                    StrokeKind::Outside,
+                );
+            }
 
-            for (idx, b) in slice.iter().enumerate() {
-                let bx = hex_col_x + (idx as f32) * (hex_group_width + char_width);
+            for (idx, (b, glyph)) in std::iter::zip(slice, &glyphs).enumerate() {
+                let cell = Some((decoder, *glyph));
+                let region = regions[idx].map(|(_, region)| region.name.as_str());
+                let bookmark = byte_bookmarks[idx];
+                let bx = hex_col_x + layout.byte_x(idx, slice.len());
 
                 let byte_rect = egui::Rect::from_min_size(
                     origin + Vec2::new(bx, y),
-                    egui::vec2(hex_group_width, row_height),
+                    egui::vec2(layout.byte_width(), row_height),
                 );
 
                 let resp = ui.interact(
                     byte_rect,
-                    ui.id().with(("hex", line, idx)),
-                    egui::Sense::click(),
+                    ui.id().with(("hex", view, line, idx)),
+                    egui::Sense::click_and_drag(),
                 );
                 // debug
                 // use bladvak::eframe::egui::{Color32, Stroke};
@@ -186,13 +498,35 @@ impl WombatApp {
                 //     egui::StrokeKind::Middle,
                 // );
 
-                let is_clicked = resp.clicked();
-                if resp.hovered() {
-                    resp.on_hover_ui(|ui| Self::ui_table_u8(ui, *b, &Accent::Hex));
+                if resp.drag_started() {
+                    drag_started = Some((offset + idx, EditColumn::Hex));
                 }
-                if is_clicked {
-                    let is_alt = ui.ctx().input(|i| i.modifiers.shift);
-                    self.selection.range = self.handle_selection_click(offset, idx, is_alt);
+                if self.selection.drag.is_some() && resp.contains_pointer() {
+                    drag_over = Some(offset + idx);
+                }
+                if resp.clicked() {
+                    clicked = Some((offset + idx, EditColumn::Hex));
+                    if bit_view && let Some(pointer) = resp.interact_pointer_pos() {
+                        #[allow(clippy::cast_sign_loss)]
+                        let bit = ((pointer.x - byte_rect.left()) / char_width).max(0.0) as usize;
+                        clicked_bit = Some((offset + idx) * 8 + bit.min(7));
+                    }
+                }
+                if resp.hovered() {
+                    resp.on_hover_ui(|ui| {
+                        Self::ui_table_u8(ui, *b, &Accent::Hex, cell);
+                        if let Some(name) = region {
+                            ui.separator();
+                            ui.label(name);
+                        }
+                        if let Some(bookmark) = bookmark {
+                            ui.separator();
+                            ui.colored_label(bookmark.color, &bookmark.name);
+                            if !bookmark.note.is_empty() {
+                                ui.label(&bookmark.note);
+                            }
+                        }
+                    });
                 }
 
                 // ASCII hover and click
@@ -206,8 +540,8 @@ impl WombatApp {
 
                 let resp = ui.interact(
                     byte_rect,
-                    ui.id().with(("ascii", line, idx)),
-                    egui::Sense::click(),
+                    ui.id().with(("ascii", view, line, idx)),
+                    egui::Sense::click_and_drag(),
                 );
                 // debug
                 // use bladvak::eframe::egui::{Color32, Stroke};
@@ -219,27 +553,183 @@ impl WombatApp {
                 //     egui::StrokeKind::Outside,
                 // );
 
+                if resp.drag_started() {
+                    drag_started = Some((offset + idx, EditColumn::Ascii));
+                }
+                if self.selection.drag.is_some() && resp.contains_pointer() {
+                    drag_over = Some(offset + idx);
+                }
                 let is_clicked = resp.clicked();
                 if resp.hovered() {
-                    resp.on_hover_ui(|ui| Self::ui_table_u8(ui, *b, &Accent::Ascii));
+                    let accent = if decoder == TextDecoder::Ascii {
+                        Accent::Ascii
+                    } else {
+                        Accent::Decoded
+                    };
+                    resp.on_hover_ui(|ui| {
+                        Self::ui_table_u8(ui, *b, &accent, cell);
+                        if let Some(name) = region {
+                            ui.separator();
+                            ui.label(name);
+                        }
+                        if let Some(bookmark) = bookmark {
+                            ui.separator();
+                            ui.colored_label(bookmark.color, &bookmark.name);
+                            if !bookmark.note.is_empty() {
+                                ui.label(&bookmark.note);
+                            }
+                        }
+                    });
                 }
                 if is_clicked {
-                    let is_alt = ui.ctx().input(|i| i.modifiers.shift);
-                    self.selection.range = self.handle_selection_click(offset, idx, is_alt);
+                    clicked = Some((offset + idx, EditColumn::Ascii));
                 }
             }
             y += row_height;
         }
+        let (is_alt, is_ctrl) = ui.ctx().input(|i| (i.modifiers.shift, i.modifiers.command));
+        if clicked.is_some() || drag_started.is_some() {
+            self.display_settings.split_view.active = view;
+        }
+        if let Some((current_idx, column)) = clicked {
+            if let Some(bit) = clicked_bit {
+                self.selection.click_bit(bit, is_alt);
+                self.selection.others.clear();
+            } else if is_ctrl {
+                if !self.selection.remove_range_at(current_idx) {
+                    self.selection.add_range(current_idx, current_idx);
+                }
+            } else {
+                self.selection.range = self.handle_selection_click(current_idx, is_alt);
+                self.selection.bits = None;
+                self.selection.others.clear();
+            }
+            self.set_cursor(current_idx, column);
+        }
+        if let Some((current_idx, column)) = drag_started {
+            if is_ctrl {
+                self.selection.add_range(current_idx, current_idx);
+            } else {
+                self.selection.range = Some((current_idx, current_idx));
+                self.selection.bits = None;
+                self.selection.others.clear();
+            }
+            self.selection.drag = Some(current_idx);
+            self.set_cursor(current_idx, column);
+        }
+        // the view where the drag started follows the pointer
+        if view == self.display_settings.split_view.active {
+            self.handle_drag(ui, drag_over, (first_line, last_line));
+        }
+    }
+
+    /// Extend the dragged range to the byte under the pointer, or to the visible edge
+    /// when the pointer is above or below the view
+    fn handle_drag(
+        &mut self,
+        ui: &egui::Ui,
+        drag_over: Option<usize>,
+        (first_line, last_line): (usize, usize),
+    ) {
+        let Some(anchor) = self.selection.drag else {
+            return;
+        };
+        let (pointer, is_down) = ui
+            .ctx()
+            .input(|i| (i.pointer.latest_pos(), i.pointer.primary_down()));
+        let bytes_per_line = self.display_settings.bytes_per_line;
+        let last_visible = (last_line * bytes_per_line).min(self.binary_file.len());
+        let over = drag_over.or_else(|| {
+            let pointer = pointer?;
+            if pointer.y < ui.clip_rect().top() {
+                Some(first_line * bytes_per_line)
+            } else if pointer.y > ui.clip_rect().bottom() {
+                last_visible.checked_sub(1)
+            } else {
+                None
+            }
+        });
+        if let Some(over) = over {
+            self.selection.range = Some((anchor.min(over), anchor.max(over)));
+        }
+        if !is_down {
+            self.selection.drag = None;
+        }
+    }
+
+    /// Scroll while a selection is dragged near the top or bottom of the view
+    fn auto_scroll(&self, ui: &egui::Ui, row_height: f32) {
+        if self.selection.drag.is_none() {
+            return;
+        }
+        let Some(pointer) = ui.ctx().pointer_latest_pos() else {
+            return;
+        };
+        let clip = ui.clip_rect();
+        let top = clip.top() + row_height;
+        let bottom = clip.bottom() - row_height;
+        // positive deltas scroll up
+        let delta = if pointer.y < top {
+            top - pointer.y
+        } else if pointer.y > bottom {
+            bottom - pointer.y
+        } else {
+            return;
+        };
+        let max_step = row_height * 2.0;
+        ui.scroll_with_delta_animation(
+            Vec2::new(0.0, delta.clamp(-max_step, max_step)),
+            egui::style::ScrollAnimation::none(),
+        );
+        ui.ctx().request_repaint();
+    }
+
+    /// Handle the cursor navigation keys
+    fn handle_cursor_keys(&mut self, ctx: &egui::Context, page_lines: usize) {
+        if ctx.memory(|m| m.focused().is_some()) {
+            return;
+        }
+        let bindings = [
+            (Key::ArrowLeft, CursorMove::Left, CursorMove::Left),
+            (Key::ArrowRight, CursorMove::Right, CursorMove::Right),
+            (Key::ArrowUp, CursorMove::Up, CursorMove::Up),
+            (Key::ArrowDown, CursorMove::Down, CursorMove::Down),
+            (Key::PageUp, CursorMove::PageUp, CursorMove::PageUp),
+            (Key::PageDown, CursorMove::PageDown, CursorMove::PageDown),
+            (Key::Home, CursorMove::LineStart, CursorMove::FileStart),
+            (Key::End, CursorMove::LineEnd, CursorMove::FileEnd),
+        ];
+        let (moves, extend) = ctx.input(|i| {
+            if i.modifiers.alt {
+                // reserved for back/forward navigation
+                return (Vec::new(), false);
+            }
+            let moves = bindings
+                .iter()
+                .filter(|(key, _, _)| i.key_pressed(*key))
+                .map(|(_, movement, command_movement)| {
+                    if i.modifiers.command {
+                        *command_movement
+                    } else {
+                        *movement
+                    }
+                })
+                .collect::<Vec<_>>();
+            (moves, i.modifiers.shift)
+        });
+        for movement in moves {
+            self.editor.pending_nibble = None;
+            self.selection.move_cursor(
+                movement,
+                extend,
+                (self.display_settings.bytes_per_line, page_lines),
+                self.binary_file.len(),
+            );
+        }
     }
 
     /// Handle selection click
-    fn handle_selection_click(
-        &self,
-        offset: usize,
-        idx: usize,
-        is_alt: bool,
-    ) -> Option<(usize, usize)> {
-        let current_idx = offset + idx;
+    fn handle_selection_click(&self, current_idx: usize, is_alt: bool) -> Option<(usize, usize)> {
         if let Some((select1, select2)) = self.selection.range {
             if is_alt {
                 if select1 == current_idx {
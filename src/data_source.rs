@@ -0,0 +1,239 @@
+//! Data source
+
+use std::io;
+use std::ops::{Deref, DerefMut, Range};
+use std::path::{Path, PathBuf};
+
+/// Largest mapped file loaded in memory to insert or remove bytes
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_RESIZE_LEN: usize = 256 * 1024 * 1024;
+
+/// Bytes of the opened file
+#[derive(Debug)]
+pub(crate) enum DataSource {
+    /// bytes held in memory
+    Memory(Vec<u8>),
+    /// copy-on-write mapping of a file, pages are only read when accessed
+    #[cfg(not(target_arch = "wasm32"))]
+    Mapped(memmap2::MmapMut),
+}
+
+impl Default for DataSource {
+    fn default() -> Self {
+        Self::Memory(Vec::new())
+    }
+}
+
+impl From<Vec<u8>> for DataSource {
+    fn from(data: Vec<u8>) -> Self {
+        Self::Memory(data)
+    }
+}
+
+impl Deref for DataSource {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Memory(data) => data,
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Mapped(map) => map,
+        }
+    }
+}
+
+impl DerefMut for DataSource {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        match self {
+            Self::Memory(data) => data,
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Mapped(map) => map,
+        }
+    }
+}
+
+impl AsRef<[u8]> for DataSource {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl DataSource {
+    /// Open a file without reading it
+    /// # Errors
+    /// Fails if the file cannot be opened or mapped
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn open(path: &Path) -> io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        if file.metadata()?.len() == 0 {
+            // empty files cannot be mapped
+            return Ok(Self::Memory(Vec::new()));
+        }
+        // SAFETY: the mapping is private, writes never reach the file.
+        // The file may still be modified by another process while mapped.
+        let map = unsafe { memmap2::MmapOptions::new().map_copy(&file)? };
+        Ok(Self::Mapped(map))
+    }
+
+    /// Open a file by reading it
+    /// # Errors
+    /// Fails if the file cannot be read
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn open(path: &Path) -> io::Result<Self> {
+        std::fs::read(path).map(Self::Memory)
+    }
+
+    /// Is the file mapped instead of loaded in memory
+    pub(crate) fn is_mapped(&self) -> bool {
+        match self {
+            Self::Memory(_) => false,
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Mapped(_) => true,
+        }
+    }
+
+    /// Can bytes be inserted or removed
+    ///
+    /// Large mapped files only accept same length replacements
+    pub(crate) fn can_resize(&self) -> bool {
+        match self {
+            Self::Memory(_) => true,
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Mapped(map) => map.len() <= MAX_RESIZE_LEN,
+        }
+    }
+
+    /// Read a mapped file in memory
+    pub(crate) fn load(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Self::Mapped(map) = self {
+            *self = Self::Memory(map.to_vec());
+        }
+    }
+
+    /// Replace the bytes in `range` by `bytes`
+    ///
+    /// Same length replacements are done in place, other ones load the file in memory,
+    /// see [`DataSource::can_resize`]
+    pub(crate) fn splice(&mut self, range: Range<usize>, bytes: &[u8]) {
+        if range.len() == bytes.len() {
+            self[range].copy_from_slice(bytes);
+            return;
+        }
+        match self {
+            Self::Memory(data) => {
+                data.splice(range, bytes.iter().copied());
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Mapped(_) => {
+                self.load();
+                self.splice(range, bytes);
+            }
+        }
+    }
+
+    /// Write the bytes to `path`
+    ///
+    /// The bytes are written to a temporary file first so a mapped file is never truncated while read,
+    /// a mapping is released before its file is replaced and then maps the written file.
+    /// A symlink is followed so the file it points to is replaced, keeping its permissions
+    /// # Errors
+    /// Fails if the file cannot be written
+    pub(crate) fn write_to(&mut self, path: &Path) -> io::Result<()> {
+        // a new file has nothing to resolve
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let path = path.as_path();
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".wombat-tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+        std::fs::write(&tmp_path, &self[..])?;
+        if let Ok(metadata) = std::fs::metadata(path) {
+            std::fs::set_permissions(&tmp_path, metadata.permissions())?;
+        }
+        if !self.is_mapped() {
+            return std::fs::rename(&tmp_path, path);
+        }
+        // the mapping must be released before its file is replaced
+        *self = Self::default();
+        match std::fs::rename(&tmp_path, path) {
+            Ok(()) => {
+                *self = Self::open(path)?;
+                Ok(())
+            }
+            Err(err) => {
+                // the bytes are kept in the temporary file
+                *self = Self::open(&tmp_path)?;
+                Err(err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::DataSource;
+
+    #[test]
+    fn test_splice_memory() {
+        let mut data = DataSource::from(vec![0, 1, 2, 3]);
+        data.splice(1..3, &[9]);
+        assert_eq!(&data[..], &[0, 9, 3]);
+        data.splice(0..1, &[7]);
+        assert_eq!(&data[..], &[7, 9, 3]);
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_mapped_copy_on_write() {
+        let path = std::env::temp_dir().join("wombat_test_mapped.bin");
+        std::fs::write(&path, [0, 1, 2, 3]).unwrap();
+        let mut data = DataSource::open(&path).unwrap();
+        assert!(data.is_mapped());
+        data.splice(0..2, &[8, 8]);
+        assert!(data.is_mapped());
+        assert_eq!(&data[..], &[8, 8, 2, 3]);
+        assert_eq!(std::fs::read(&path).unwrap(), vec![0, 1, 2, 3]);
+        assert!(data.can_resize());
+        data.splice(3..4, &[]);
+        assert!(!data.is_mapped());
+        data.write_to(&path).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), vec![8, 8, 2]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_write_mapped() {
+        let path = std::env::temp_dir().join("wombat_test_write_mapped.bin");
+        std::fs::write(&path, [0, 1, 2, 3]).unwrap();
+        let mut data = DataSource::open(&path).unwrap();
+        data.splice(1..2, &[9]);
+        data.write_to(&path).unwrap();
+        assert!(data.is_mapped());
+        assert_eq!(&data[..], &[0, 9, 2, 3]);
+        assert_eq!(std::fs::read(&path).unwrap(), vec![0, 9, 2, 3]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_write_through_symlink() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir();
+        let path = dir.join("wombat_test_write_target.bin");
+        let link = dir.join("wombat_test_write_link.bin");
+        std::fs::write(&path, [0, 1]).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink(&path, &link).unwrap();
+        let mut data = DataSource::from(vec![7, 7, 7]);
+        data.write_to(&link).unwrap();
+        assert!(std::fs::symlink_metadata(&link).unwrap().is_symlink());
+        assert_eq!(std::fs::read(&path).unwrap(), vec![7, 7, 7]);
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+        std::fs::remove_file(&link).unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,157 @@
+//! Opened documents
+
+use bladvak::eframe::egui;
+use std::mem::swap;
+use std::path::{Path, PathBuf};
+
+use crate::WombatApp;
+use crate::data_source::DataSource;
+use crate::history::History;
+use crate::panels::FileInfoData;
+use crate::selection::Selection;
+use crate::windows::WindowsData;
+
+/// Opened file and its state
+#[derive(Debug)]
+pub(crate) struct Document {
+    /// Binary file data
+    binary_file: DataSource,
+    /// Filename of the file
+    filename: PathBuf,
+    /// Selection
+    selection: Selection,
+    /// Key of the bookmarks of the file
+    bookmark_key: Option<String>,
+    /// File info
+    file_format: Option<FileInfoData>,
+    /// Windows
+    windows_data: WindowsData,
+    /// Edit history
+    history: History,
+    /// Buffer has unsaved changes
+    dirty: bool,
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Self::new(DataSource::default(), PathBuf::new())
+    }
+}
+
+impl Document {
+    /// New document
+    pub(crate) fn new(binary_file: DataSource, filename: PathBuf) -> Self {
+        Self {
+            binary_file,
+            filename,
+            selection: Selection::default(),
+            bookmark_key: None,
+            file_format: None,
+            windows_data: WindowsData::new(),
+            history: History::default(),
+            dirty: false,
+        }
+    }
+
+    /// Name shown in the tab
+    fn tab_name(filename: &Path, dirty: bool) -> String {
+        let name = filename.file_name().map_or_else(
+            || filename.display().to_string(),
+            |name| name.display().to_string(),
+        );
+        let dirty_mark = if dirty { " *" } else { "" };
+        format!("{name}{dirty_mark}")
+    }
+}
+
+impl WombatApp {
+    /// Exchange the active document with `document`
+    fn swap_document(&mut self, document: &mut Document) {
+        swap(&mut self.binary_file, &mut document.binary_file);
+        swap(&mut self.filename, &mut document.filename);
+        swap(&mut self.selection, &mut document.selection);
+        swap(&mut self.bookmark_key, &mut document.bookmark_key);
+        swap(&mut self.file_format, &mut document.file_format);
+        swap(&mut self.windows_data, &mut document.windows_data);
+        swap(&mut self.history, &mut document.history);
+        swap(&mut self.dirty, &mut document.dirty);
+        self.display_settings.minimap.reset();
+    }
+
+    /// Make the document at `idx` the active one
+    pub(crate) fn select_document(&mut self, idx: usize) {
+        if idx == self.active_document || idx >= self.documents.len() {
+            return;
+        }
+        let mut documents = std::mem::take(&mut self.documents);
+        // the active document lives in the app, its slot holds a placeholder
+        // a pending edit belongs to the previous document
+        self.pending_edit = None;
+        self.swap_document(&mut documents[self.active_document]);
+        self.swap_document(&mut documents[idx]);
+        self.documents = documents;
+        self.active_document = idx;
+        self.editor.pending_nibble = None;
+    }
+
+    /// Open a file in a new tab
+    pub(crate) fn open_document(&mut self, binary_file: DataSource, filename: PathBuf) {
+        self.documents.push(Document::new(binary_file, filename));
+        self.select_document(self.documents.len() - 1);
+        self.selection.clamp(self.binary_file.len());
+    }
+
+    /// Close the active document, the default file is opened when it was the last one
+    pub(crate) fn close_document(&mut self) {
+        let closed = self.active_document;
+        if self.documents.len() <= 1 {
+            let bladvak::File { data, path } = Self::load_default_file();
+            self.swap_document(&mut Document::new(DataSource::from(data), path));
+            return;
+        }
+        let next = if closed + 1 < self.documents.len() {
+            closed + 1
+        } else {
+            closed - 1
+        };
+        self.select_document(next);
+        self.documents.remove(closed);
+        if self.active_document > closed {
+            self.active_document -= 1;
+        }
+    }
+
+    /// Show the document tabs
+    pub(crate) fn ui_tabs(&mut self, ui: &mut egui::Ui) {
+        let mut selected = None;
+        let mut closed = None;
+        for (idx, document) in self.documents.iter().enumerate() {
+            let is_active = idx == self.active_document;
+            let (filename, dirty) = if is_active {
+                (&self.filename, self.dirty)
+            } else {
+                (&document.filename, document.dirty)
+            };
+            let tab = ui
+                .selectable_label(is_active, Document::tab_name(filename, dirty))
+                .on_hover_text(filename.display().to_string());
+            if tab.clicked() {
+                selected = Some(idx);
+            }
+            if ui.small_button("x").on_hover_text("Close").clicked() {
+                closed = Some(idx);
+            }
+        }
+        if let Some(idx) = selected {
+            self.select_document(idx);
+        }
+        if let Some(idx) = closed {
+            self.select_document(idx);
+            if self.dirty {
+                self.closing_document = true;
+            } else {
+                self.close_document();
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! Byte editor
+
+use bladvak::eframe::egui::{self, Color32, Event, Key};
+use bladvak::errors::{AppError, ErrorManager};
+
+use crate::WombatApp;
+use crate::history::Edit;
+
+/// Column receiving the typed input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum EditColumn {
+    /// hex digits
+    Hex,
+    /// characters
+    Ascii,
+}
+
+/// Editor state
+#[derive(Debug)]
+pub(crate) struct Editor {
+    /// column receiving the typed input
+    pub(crate) column: EditColumn,
+    /// insert bytes instead of overwriting them
+    pub(crate) insert_mode: bool,
+    /// high nibble typed in the hex column, waiting for the low nibble
+    pub(crate) pending_nibble: Option<u8>,
+    /// modified bytes color (light theme, dark theme)
+    pub(crate) modified_color: (Color32, Color32),
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Self {
+            column: EditColumn::Hex,
+            insert_mode: false,
+            pending_nibble: None,
+            modified_color: (Color32::RED, Color32::LIGHT_RED),
+        }
+    }
+}
+
+impl Editor {
+    /// Short name of the current mode
+    pub(crate) fn mode_name(&self) -> &'static str {
+        if self.insert_mode { "INS" } else { "OVR" }
+    }
+}
+
+impl WombatApp {
+    /// Place the edit cursor on a byte
+    pub(crate) fn set_cursor(&mut self, offset: usize, column: EditColumn) {
+        self.selection.cursor = Some(offset);
+        self.selection.anchor = None;
+        self.editor.column = column;
+        self.editor.pending_nibble = None;
+    }
+
+    /// Handle the keyboard input of the editor
+    pub(crate) fn handle_edit_input(
+        &mut self,
+        ctx: &egui::Context,
+        error_manager: &mut ErrorManager,
+    ) {
+        let Some(mut cursor) = self.selection.cursor else {
+            return;
+        };
+        if ctx.memory(|m| m.focused().is_some()) {
+            // typing in a text field
+            return;
+        }
+        let (events, toggle_insert, escape, delete, backspace) = ctx.input(|i| {
+            (
+                i.events.clone(),
+                i.key_pressed(Key::Insert),
+                i.key_pressed(Key::Escape),
+                i.key_pressed(Key::Delete),
+                i.key_pressed(Key::Backspace),
+            )
+        });
+        if toggle_insert {
+            self.editor.insert_mode = !self.editor.insert_mode;
+            self.editor.pending_nibble = None;
+        }
+        if escape {
+            self.selection.cursor = None;
+            self.editor.pending_nibble = None;
+            return;
+        }
+        if delete || backspace {
+            self.editor.pending_nibble = None;
+            if backspace {
+                let Some(previous) = cursor.checked_sub(1) else {
+                    return;
+                };
+                cursor = previous;
+            }
+            if cursor < self.binary_file.len()
+                && let Err(err) = self.apply_edit(Edit::delete(&self.binary_file, cursor..=cursor))
+            {
+                error_manager.add_error(err);
+            }
+            self.selection.cursor = Some(cursor.min(self.binary_file.len()));
+            self.selection.clamp(self.binary_file.len());
+            return;
+        }
+        for event in events {
+            let Event::Text(text) = event else {
+                continue;
+            };
+            for c in text.chars() {
+                let written = match self.editor.column {
+                    EditColumn::Hex => {
+                        let Some(digit) = c.to_digit(16).and_then(|d| u8::try_from(d).ok()) else {
+                            continue;
+                        };
+                        if let Some(high) = self.editor.pending_nibble.take() {
+                            self.write_bytes(cursor, vec![(high << 4) | digit])
+                        } else {
+                            self.editor.pending_nibble = Some(digit);
+                            continue;
+                        }
+                    }
+                    EditColumn::Ascii => {
+                        let mut buf = [0u8; 4];
+                        let bytes = c.encode_utf8(&mut buf).as_bytes().to_vec();
+                        self.write_bytes(cursor, bytes)
+                    }
+                };
+                match written {
+                    Ok(next) => cursor = next,
+                    Err(err) => {
+                        // the remaining input would fail the same way
+                        error_manager.add_error(err);
+                        self.selection.cursor = Some(cursor);
+                        return;
+                    }
+                }
+            }
+        }
+        self.selection.cursor = Some(cursor);
+    }
+
+    /// Write bytes at the cursor and return the new cursor, unchanged if the edit waits
+    /// # Errors
+    /// Fails if the bytes cannot be inserted
+    fn write_bytes(&mut self, cursor: usize, mut bytes: Vec<u8>) -> Result<usize, AppError> {
+        if self.display_settings.display_lsb {
+            for b in &mut bytes {
+                *b = b.reverse_bits();
+            }
+        }
+        let nb_bytes = bytes.len();
+        let edit = if self.editor.insert_mode || cursor >= self.binary_file.len() {
+            Edit::insert(cursor, bytes)
+        } else {
+            Edit::overwrite(&self.binary_file, cursor, bytes)
+        };
+        if self.apply_edit(edit)? {
+            Ok(cursor + nb_bytes)
+        } else {
+            Ok(cursor)
+        }
+    }
+}
@@ -0,0 +1,632 @@
+//! Edit history
+
+use bladvak::eframe::egui::{self, Key, KeyboardShortcut, Modifiers};
+use bladvak::errors::{AppError, ErrorManager};
+use std::collections::BTreeSet;
+use std::ops::{Range, RangeInclusive};
+
+use crate::WombatApp;
+use crate::bookmarks::BookmarkChange;
+use crate::data_source::DataSource;
+
+/// Kind of edit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum EditKind {
+    /// bytes removed
+    Delete,
+    /// bytes added
+    Insert,
+    /// bytes replaced in place
+    Overwrite,
+    /// occurrences replaced
+    Replace,
+}
+
+impl EditKind {
+    /// Name of the edit kind
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            EditKind::Delete => "Delete",
+            EditKind::Insert => "Insert",
+            EditKind::Overwrite => "Overwrite",
+            EditKind::Replace => "Replace",
+        }
+    }
+}
+
+/// Replace `removed` by `inserted` at `offset`
+#[derive(Debug, Clone)]
+pub(crate) struct Splice {
+    /// start of the splice
+    pub(crate) offset: usize,
+    /// bytes present before the edit
+    pub(crate) removed: Vec<u8>,
+    /// bytes present after the edit
+    pub(crate) inserted: Vec<u8>,
+}
+
+impl Splice {
+    /// Apply the splice
+    fn apply(&self, data: &mut DataSource) {
+        let end = self.offset + self.removed.len();
+        data.splice(self.offset..end, &self.inserted);
+    }
+
+    /// Revert the splice
+    fn revert(&self, data: &mut DataSource) {
+        let end = self.offset + self.inserted.len();
+        data.splice(self.offset..end, &self.removed);
+    }
+}
+
+/// One undoable operation
+#[derive(Debug, Clone)]
+pub(crate) struct Edit {
+    /// kind of edit
+    pub(crate) kind: EditKind,
+    /// splices, applied in order
+    pub(crate) splices: Vec<Splice>,
+    /// bookmarks changed the last time the edit was applied or reverted
+    pub(crate) bookmarks: Vec<BookmarkChange>,
+}
+
+impl Edit {
+    /// Delete the bytes in `range`
+    pub(crate) fn delete(data: &[u8], range: RangeInclusive<usize>) -> Self {
+        Self::delete_ranges(data, &[(*range.start(), *range.end())])
+    }
+
+    /// Delete the bytes of every range, ranges must be sorted and must not overlap
+    pub(crate) fn delete_ranges(data: &[u8], ranges: &[(usize, usize)]) -> Self {
+        let mut deleted = 0;
+        let splices = ranges
+            .iter()
+            .map(|(start, end)| {
+                let removed = data.get(*start..=*end).unwrap_or_default().to_vec();
+                // offsets are shifted by the previous splices
+                let offset = start - deleted;
+                deleted += removed.len();
+                Splice {
+                    offset,
+                    removed,
+                    inserted: vec![],
+                }
+            })
+            .collect();
+        Self {
+            kind: EditKind::Delete,
+            splices,
+            bookmarks: Vec::new(),
+        }
+    }
+
+    /// Insert `bytes` at `offset`
+    pub(crate) fn insert(offset: usize, bytes: Vec<u8>) -> Self {
+        Self {
+            kind: EditKind::Insert,
+            splices: vec![Splice {
+                offset,
+                removed: vec![],
+                inserted: bytes,
+            }],
+            bookmarks: Vec::new(),
+        }
+    }
+
+    /// Overwrite the bytes starting at `offset` with `bytes`
+    pub(crate) fn overwrite(data: &[u8], offset: usize, bytes: Vec<u8>) -> Self {
+        let end = offset + bytes.len();
+        Self::overwrite_range(data, offset..end, bytes)
+    }
+
+    /// Overwrite the bytes in `range` with `bytes`, which may have another length
+    pub(crate) fn overwrite_range(data: &[u8], range: Range<usize>, bytes: Vec<u8>) -> Self {
+        let offset = range.start;
+        let end = range.end.min(data.len());
+        let removed = data.get(offset..end).unwrap_or_default().to_vec();
+        Self {
+            kind: EditKind::Overwrite,
+            splices: vec![Splice {
+                offset,
+                removed,
+                inserted: bytes,
+            }],
+            bookmarks: Vec::new(),
+        }
+    }
+
+    /// Replace every range by `replacement`, ranges must be sorted and must not overlap
+    pub(crate) fn replace(data: &[u8], ranges: &[Range<usize>], replacement: &[u8]) -> Self {
+        let mut delta = 0isize;
+        let splices = ranges
+            .iter()
+            .map(|range| {
+                let removed = data.get(range.clone()).unwrap_or_default().to_vec();
+                // offsets are shifted by the previous splices
+                let offset = range.start.saturating_add_signed(delta);
+                delta += replacement.len().cast_signed() - removed.len().cast_signed();
+                Splice {
+                    offset,
+                    removed,
+                    inserted: replacement.to_vec(),
+                }
+            })
+            .collect();
+        Self {
+            kind: EditKind::Replace,
+            splices,
+            bookmarks: Vec::new(),
+        }
+    }
+
+    /// Apply the edit
+    fn apply(&self, data: &mut DataSource) {
+        for splice in &self.splices {
+            splice.apply(data);
+        }
+    }
+
+    /// Revert the edit
+    fn revert(&self, data: &mut DataSource) {
+        for splice in self.splices.iter().rev() {
+            splice.revert(data);
+        }
+    }
+
+    /// Offset, removed and inserted lengths of each splice, in the order they change the data
+    pub(crate) fn shifts(&self, reverted: bool) -> Vec<(usize, usize, usize)> {
+        if reverted {
+            self.splices
+                .iter()
+                .rev()
+                .map(|s| (s.offset, s.inserted.len(), s.removed.len()))
+                .collect()
+        } else {
+            self.splices
+                .iter()
+                .map(|s| (s.offset, s.removed.len(), s.inserted.len()))
+                .collect()
+        }
+    }
+
+    /// Does the edit insert or remove bytes
+    pub(crate) fn changes_length(&self) -> bool {
+        self.splices
+            .iter()
+            .any(|s| s.removed.len() != s.inserted.len())
+    }
+
+    /// Byte range covered by the edit once applied
+    pub(crate) fn range(&self) -> Option<RangeInclusive<usize>> {
+        let start = self.splices.iter().map(|s| s.offset).min()?;
+        let end = self
+            .splices
+            .iter()
+            .map(|s| s.offset + s.inserted.len().max(s.removed.len()))
+            .max()?;
+        Some(start..=end.checked_sub(1)?.max(start))
+    }
+
+    /// Short description of the edit
+    pub(crate) fn description(&self) -> String {
+        let removed: usize = self.splices.iter().map(|s| s.removed.len()).sum();
+        let inserted: usize = self.splices.iter().map(|s| s.inserted.len()).sum();
+        match self.kind {
+            EditKind::Delete => format!("{removed} bytes"),
+            EditKind::Insert | EditKind::Overwrite => format!("{inserted} bytes"),
+            EditKind::Replace => format!("{} occurrences", self.splices.len()),
+        }
+    }
+}
+
+/// Edit journal
+#[derive(Debug)]
+pub(crate) struct History {
+    /// edits that can be undone, oldest first
+    pub(crate) undo_stack: Vec<Edit>,
+    /// edits that can be redone, most recently undone last
+    pub(crate) redo_stack: Vec<Edit>,
+    /// offsets of the bytes changed by the edits
+    modified: BTreeSet<usize>,
+    /// number of applied edits when the file was saved, none if that state is lost
+    saved: Option<usize>,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            modified: BTreeSet::new(),
+            saved: Some(0),
+        }
+    }
+}
+
+impl History {
+    /// Apply a new edit and record it
+    fn push(&mut self, edit: Edit, data: &mut DataSource) {
+        if self
+            .saved
+            .is_some_and(|saved| saved > self.undo_stack.len())
+        {
+            // the saved state was in the discarded redo stack
+            self.saved = None;
+        }
+        edit.apply(data);
+        self.mark_applied(&edit);
+        self.undo_stack.push(edit);
+        self.redo_stack.clear();
+    }
+
+    /// Undo the last edit
+    fn undo(&mut self, data: &mut DataSource) -> Option<&mut Edit> {
+        let edit = self.undo_stack.pop()?;
+        edit.revert(data);
+        self.mark_reverted();
+        self.redo_stack.push(edit);
+        self.redo_stack.last_mut()
+    }
+
+    /// Redo the last undone edit
+    fn redo(&mut self, data: &mut DataSource) -> Option<&mut Edit> {
+        let edit = self.redo_stack.pop()?;
+        edit.apply(data);
+        self.mark_applied(&edit);
+        self.undo_stack.push(edit);
+        self.undo_stack.last_mut()
+    }
+
+    /// Remember the current state as the saved one
+    pub(crate) fn mark_saved(&mut self) {
+        self.saved = Some(self.undo_stack.len());
+    }
+
+    /// Forget the saved state, the buffer was never saved
+    pub(crate) fn mark_unsaved(&mut self) {
+        self.saved = None;
+    }
+
+    /// Is the buffer in its saved state
+    pub(crate) fn is_saved(&self) -> bool {
+        self.saved == Some(self.undo_stack.len())
+    }
+
+    /// Is the byte at `offset` modified
+    pub(crate) fn is_modified(&self, offset: usize) -> bool {
+        self.modified.contains(&offset)
+    }
+
+    /// Move the modified offsets after a splice of `removed` bytes replaced by `inserted` bytes
+    fn shift_modified(&mut self, offset: usize, removed: usize, inserted: usize) {
+        let tail = self.modified.split_off(&offset);
+        self.modified.extend(
+            tail.into_iter()
+                .filter(|idx| *idx >= offset + removed)
+                .map(|idx| idx - removed + inserted),
+        );
+    }
+
+    /// Track the bytes changed by an applied edit
+    fn mark_applied(&mut self, edit: &Edit) {
+        for splice in &edit.splices {
+            let Splice {
+                offset,
+                removed,
+                inserted,
+            } = splice;
+            self.shift_modified(*offset, removed.len(), inserted.len());
+            self.modified.extend(*offset..offset + inserted.len());
+        }
+    }
+
+    /// Track the bytes restored by a reverted edit, replaying the edits still applied
+    /// since the reverted one may have covered bytes they changed
+    fn mark_reverted(&mut self) {
+        self.modified.clear();
+        for edit in std::mem::take(&mut self.undo_stack) {
+            self.mark_applied(&edit);
+            self.undo_stack.push(edit);
+        }
+    }
+}
+
+/// Edit waiting for the user to accept loading a mapped file in memory
+#[derive(Debug)]
+pub(crate) enum PendingEdit {
+    /// new edit
+    Apply(Edit),
+    /// undo of the last edit
+    Undo,
+    /// redo of the last undone edit
+    Redo,
+}
+
+impl WombatApp {
+    /// Can `edit` be applied now, false if the file must be loaded in memory first
+    /// # Errors
+    /// Fails if the edit changes the length of a file too large to be loaded
+    fn check_edit(&self, edit: &Edit) -> Result<bool, AppError> {
+        if !edit.changes_length() || !self.binary_file.is_mapped() {
+            return Ok(true);
+        }
+        if !self.binary_file.can_resize() {
+            return Err(AppError::from(
+                "The file is too large to insert or remove bytes, bytes can only be overwritten",
+            ));
+        }
+        Ok(false)
+    }
+
+    /// Apply an edit to the binary file and record it in the history,
+    /// returns false if the edit waits for the file to be loaded in memory
+    /// # Errors
+    /// Fails if the edit changes the length of a file too large to be loaded
+    pub(crate) fn apply_edit(&mut self, mut edit: Edit) -> Result<bool, AppError> {
+        if !self.check_edit(&edit)? {
+            self.pending_edit = Some(PendingEdit::Apply(edit));
+            return Ok(false);
+        }
+        self.bookmarks
+            .shift(self.bookmark_key.as_deref(), &mut edit, false);
+        self.windows_data.search.shift(&edit, false);
+        self.history.push(edit, &mut self.binary_file);
+        self.dirty = !self.history.is_saved();
+        self.stale();
+        Ok(true)
+    }
+
+    /// Undo the last edit
+    /// # Errors
+    /// Fails if the edit changes the length of a file too large to be loaded
+    pub(crate) fn undo(&mut self) -> Result<(), AppError> {
+        if let Some(edit) = self.history.undo_stack.last()
+            && !self.check_edit(edit)?
+        {
+            self.pending_edit = Some(PendingEdit::Undo);
+            return Ok(());
+        }
+        let range = self
+            .history
+            .undo(&mut self.binary_file)
+            .map(|edit| {
+                self.bookmarks
+                    .shift(self.bookmark_key.as_deref(), edit, true);
+                self.windows_data.search.shift(edit, true);
+                &*edit
+            })
+            .and_then(|edit| match edit.kind {
+                EditKind::Insert => None,
+                EditKind::Delete | EditKind::Overwrite | EditKind::Replace => edit.range(),
+            });
+        self.select_after_edit(range);
+        Ok(())
+    }
+
+    /// Redo the last undone edit
+    /// # Errors
+    /// Fails if the edit changes the length of a file too large to be loaded
+    pub(crate) fn redo(&mut self) -> Result<(), AppError> {
+        if let Some(edit) = self.history.redo_stack.last()
+            && !self.check_edit(edit)?
+        {
+            self.pending_edit = Some(PendingEdit::Redo);
+            return Ok(());
+        }
+        let range = self
+            .history
+            .redo(&mut self.binary_file)
+            .map(|edit| {
+                self.bookmarks
+                    .shift(self.bookmark_key.as_deref(), edit, false);
+                self.windows_data.search.shift(edit, false);
+                &*edit
+            })
+            .and_then(|edit| match edit.kind {
+                EditKind::Delete => None,
+                EditKind::Insert | EditKind::Overwrite | EditKind::Replace => edit.range(),
+            });
+        self.select_after_edit(range);
+        Ok(())
+    }
+
+    /// Select the bytes touched by an undo/redo
+    fn select_after_edit(&mut self, range: Option<RangeInclusive<usize>>) {
+        let file_len = self.binary_file.len();
+        self.dirty = !self.history.is_saved();
+        self.selection.range = range
+            .filter(|range| *range.start() < file_len)
+            .map(|range| (*range.start(), (*range.end()).min(file_len - 1)));
+        // the other ranges and the cursor may be past the end
+        self.selection.clamp(file_len);
+        self.stale();
+    }
+
+    /// Ask before loading a mapped file in memory to insert or remove bytes
+    pub(crate) fn ui_load_confirmation(
+        &mut self,
+        ui: &mut egui::Ui,
+        error_manager: &mut ErrorManager,
+    ) {
+        if self.pending_edit.is_none() {
+            return;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let size_mib = self.binary_file.len() as f64 / 1024.0 / 1024.0;
+        let mut load = false;
+        let mut cancel = false;
+        egui::Modal::new(egui::Id::new("load_confirmation")).show(ui.ctx(), |ui| {
+            ui.heading("Load the file in memory");
+            ui.label(format!(
+                "The file is mapped from disk. Inserting or removing bytes loads all of it \
+                 ({size_mib:.1} MiB) in memory."
+            ));
+            ui.separator();
+            ui.horizontal(|ui| {
+                load = ui.button("Load and edit").clicked();
+                cancel = ui.button("Cancel").clicked();
+            });
+        });
+        if cancel {
+            self.pending_edit = None;
+        }
+        if !load {
+            return;
+        }
+        self.binary_file.load();
+        let result = match self.pending_edit.take() {
+            Some(PendingEdit::Apply(edit)) => self.apply_edit(edit).map(|_| ()),
+            Some(PendingEdit::Undo) => self.undo(),
+            Some(PendingEdit::Redo) => self.redo(),
+            None => Ok(()),
+        };
+        if let Err(err) = result {
+            error_manager.add_error(err);
+        }
+        self.selection.clamp(self.binary_file.len());
+    }
+
+    /// Handle undo/redo keyboard shortcuts
+    pub(crate) fn handle_history_shortcuts(
+        &mut self,
+        ctx: &egui::Context,
+        error_manager: &mut ErrorManager,
+    ) {
+        if ctx.memory(|m| m.focused().is_some()) {
+            // a text field is handling its own undo
+            return;
+        }
+        let redo = KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z);
+        let undo = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+        let result = if ctx.input_mut(|i| i.consume_shortcut(&redo)) {
+            self.redo()
+        } else if ctx.input_mut(|i| i.consume_shortcut(&undo)) {
+            self.undo()
+        } else {
+            Ok(())
+        };
+        if let Err(err) = result {
+            error_manager.add_error(err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Edit, History};
+    use crate::data_source::DataSource;
+
+    #[test]
+    fn test_delete_undo_redo() {
+        let mut data = DataSource::from(vec![0, 1, 2, 3, 4]);
+        let mut history = History::default();
+        history.push(Edit::delete(&data, 1..=2), &mut data);
+        assert_eq!(&data[..], &[0, 3, 4]);
+        history.undo(&mut data);
+        assert_eq!(&data[..], &[0, 1, 2, 3, 4]);
+        history.redo(&mut data);
+        assert_eq!(&data[..], &[0, 3, 4]);
+    }
+
+    #[test]
+    fn test_delete_ranges() {
+        let mut data = DataSource::from(vec![0, 1, 2, 3, 4, 5, 6]);
+        let mut history = History::default();
+        history.push(Edit::delete_ranges(&data, &[(1, 2), (5, 5)]), &mut data);
+        assert_eq!(&data[..], &[0, 3, 4, 6]);
+        history.undo(&mut data);
+        assert_eq!(&data[..], &[0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_insert_and_overwrite() {
+        let mut data = DataSource::from(vec![0, 1, 2]);
+        let mut history = History::default();
+        history.push(Edit::insert(1, vec![9, 9]), &mut data);
+        assert_eq!(&data[..], &[0, 9, 9, 1, 2]);
+        assert!(Edit::insert(0, vec![1]).changes_length());
+        assert!(!Edit::overwrite(&data, 3, vec![7, 7]).changes_length());
+        history.push(Edit::overwrite(&data, 3, vec![7, 7, 7]), &mut data);
+        assert_eq!(&data[..], &[0, 9, 9, 7, 7, 7]);
+        assert!(history.is_modified(1) && history.is_modified(5));
+        assert!(!history.is_modified(0));
+        history.undo(&mut data);
+        assert_eq!(&data[..], &[0, 9, 9, 1, 2]);
+        assert!(!history.is_modified(3));
+        history.undo(&mut data);
+        assert_eq!(&data[..], &[0, 1, 2]);
+        assert!(!history.is_modified(1));
+        assert!(history.undo(&mut data).is_none());
+    }
+
+    #[test]
+    fn test_modified_shift_on_delete() {
+        let mut data = DataSource::from(vec![0, 1, 2, 3, 4]);
+        let mut history = History::default();
+        history.push(Edit::overwrite(&data, 4, vec![8]), &mut data);
+        history.push(Edit::delete(&data, 0..=1), &mut data);
+        assert_eq!(&data[..], &[2, 3, 8]);
+        assert!(history.is_modified(2));
+        assert!(!history.is_modified(4));
+    }
+
+    #[test]
+    fn test_modified_after_undo_of_overwrite() {
+        let mut data = DataSource::from(vec![0, 1, 2, 3]);
+        let mut history = History::default();
+        history.push(Edit::overwrite(&data, 1, vec![8, 8]), &mut data);
+        history.push(Edit::overwrite(&data, 2, vec![9]), &mut data);
+        history.undo(&mut data);
+        assert_eq!(&data[..], &[0, 8, 8, 3]);
+        // the byte is still changed by the first edit
+        assert!(history.is_modified(1) && history.is_modified(2));
+        assert!(!history.is_modified(3));
+    }
+
+    #[test]
+    fn test_replace_changing_length() {
+        let mut data = DataSource::from(b"ab--ab--ab".to_vec());
+        let mut history = History::default();
+        let ranges = [0..2, 4..6, 8..10];
+        history.push(Edit::replace(&data, &ranges, b"xyz"), &mut data);
+        assert_eq!(&data[..], b"xyz--xyz--xyz");
+        history.undo(&mut data);
+        assert_eq!(&data[..], b"ab--ab--ab");
+        history.push(Edit::replace(&data, &ranges, b""), &mut data);
+        assert_eq!(&data[..], b"----");
+        history.undo(&mut data);
+        assert_eq!(&data[..], b"ab--ab--ab");
+    }
+
+    #[test]
+    fn test_new_edit_clears_redo() {
+        let mut data = DataSource::from(vec![0, 1, 2]);
+        let mut history = History::default();
+        history.push(Edit::overwrite(&data, 0, vec![5]), &mut data);
+        assert_eq!(&data[..], &[5, 1, 2]);
+        history.undo(&mut data);
+        assert_eq!(&data[..], &[0, 1, 2]);
+        history.push(Edit::delete(&data, 0..=0), &mut data);
+        assert!(history.redo(&mut data).is_none());
+        assert_eq!(&data[..], &[1, 2]);
+    }
+
+    #[test]
+    fn test_saved_state() {
+        let mut data = DataSource::from(vec![0, 1, 2]);
+        let mut history = History::default();
+        assert!(history.is_saved());
+        history.push(Edit::overwrite(&data, 0, vec![5]), &mut data);
+        assert!(!history.is_saved());
+        history.undo(&mut data);
+        assert!(history.is_saved());
+        history.redo(&mut data);
+        history.mark_saved();
+        history.undo(&mut data);
+        assert!(!history.is_saved());
+        history.push(Edit::delete(&data, 0..=0), &mut data);
+        history.undo(&mut data);
+        // the saved edit was discarded by the new one
+        assert!(!history.is_saved());
+    }
+}
@@ -0,0 +1,258 @@
+//! GUID, network address and port values
+
+use std::fmt::Write;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Vendors of well known MAC address prefixes, sorted by prefix
+const OUI_VENDORS: [([u8; 3], &str); 28] = [
+    ([0x00, 0x00, 0x0C], "Cisco"),
+    ([0x00, 0x00, 0x5E], "IANA"),
+    ([0x00, 0x02, 0xB3], "Intel"),
+    ([0x00, 0x03, 0x93], "Apple"),
+    ([0x00, 0x04, 0x4B], "NVIDIA"),
+    ([0x00, 0x05, 0x69], "VMware"),
+    ([0x00, 0x09, 0x0F], "Fortinet"),
+    ([0x00, 0x0A, 0xF7], "Broadcom"),
+    ([0x00, 0x0C, 0x29], "VMware"),
+    ([0x00, 0x0D, 0x3A], "Microsoft"),
+    ([0x00, 0x10, 0x18], "Broadcom"),
+    ([0x00, 0x14, 0x22], "Dell"),
+    ([0x00, 0x15, 0x5D], "Microsoft Hyper-V"),
+    ([0x00, 0x16, 0x3E], "Xen"),
+    ([0x00, 0x17, 0x88], "Philips Lighting"),
+    ([0x00, 0x1A, 0x11], "Google"),
+    ([0x00, 0x1B, 0x17], "Palo Alto Networks"),
+    ([0x00, 0x1B, 0x21], "Intel"),
+    ([0x00, 0x1B, 0x63], "Apple"),
+    ([0x00, 0x1C, 0x14], "VMware"),
+    ([0x00, 0x1C, 0x42], "Parallels"),
+    ([0x00, 0x25, 0x90], "Super Micro"),
+    ([0x00, 0x50, 0x56], "VMware"),
+    ([0x00, 0x60, 0x2F], "Cisco"),
+    ([0x00, 0xE0, 0x4C], "Realtek"),
+    ([0x08, 0x00, 0x27], "Oracle VirtualBox"),
+    ([0x52, 0x54, 0x00], "QEMU/KVM"),
+    ([0xB8, 0x27, 0xEB], "Raspberry Pi"),
+];
+
+/// Vendor of a MAC address
+pub(crate) fn mac_vendor(mac: &[u8]) -> Option<&'static str> {
+    let prefix = mac.get(..3)?;
+    OUI_VENDORS
+        .binary_search_by(|(oui, _)| oui.as_slice().cmp(prefix))
+        .ok()
+        .map(|idx| OUI_VENDORS[idx].1)
+}
+
+/// Parse `text` as hex digits pairs separated by one of `separators`
+fn parse_hex_groups(text: &str, separators: &[char], size: usize) -> Option<Vec<u8>> {
+    let digits = text
+        .trim()
+        .chars()
+        .filter(|c| !separators.contains(c))
+        .collect::<String>();
+    if digits.len() != size * 2 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..size)
+        .map(|idx| u8::from_str_radix(&digits[idx * 2..idx * 2 + 2], 16).ok())
+        .collect()
+}
+
+/// Kind of value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Identifier {
+    /// GUID with the first three fields in little endian
+    GuidMicrosoft,
+    /// GUID in RFC 4122 byte order
+    GuidRfc,
+    /// IPv4 address
+    Ipv4,
+    /// IPv6 address
+    Ipv6,
+    /// MAC address
+    Mac,
+    /// port number in network order
+    Port,
+}
+
+impl Identifier {
+    /// Every kind
+    pub(crate) const ALL: [Identifier; 6] = [
+        Identifier::GuidMicrosoft,
+        Identifier::GuidRfc,
+        Identifier::Ipv4,
+        Identifier::Ipv6,
+        Identifier::Mac,
+        Identifier::Port,
+    ];
+
+    /// Name of the kind
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Identifier::GuidMicrosoft => "GUID (Microsoft)",
+            Identifier::GuidRfc => "GUID (RFC 4122)",
+            Identifier::Ipv4 => "IPv4",
+            Identifier::Ipv6 => "IPv6",
+            Identifier::Mac => "MAC",
+            Identifier::Port => "Port",
+        }
+    }
+
+    /// Size in bytes
+    pub(crate) fn size(self) -> usize {
+        match self {
+            Identifier::GuidMicrosoft | Identifier::GuidRfc | Identifier::Ipv6 => 16,
+            Identifier::Ipv4 => 4,
+            Identifier::Mac => 6,
+            Identifier::Port => 2,
+        }
+    }
+
+    /// Text of the value in `bytes`, which must have the size of the kind
+    pub(crate) fn decode(self, bytes: &[u8]) -> Option<String> {
+        if bytes.len() != self.size() {
+            return None;
+        }
+        let mut text = String::new();
+        match self {
+            Identifier::GuidMicrosoft | Identifier::GuidRfc => {
+                let mut bytes = <[u8; 16]>::try_from(bytes).ok()?;
+                if self == Identifier::GuidMicrosoft {
+                    Self::swap_guid_fields(&mut bytes);
+                }
+                for (idx, byte) in bytes.iter().enumerate() {
+                    if matches!(idx, 4 | 6 | 8 | 10) {
+                        text.push('-');
+                    }
+                    write!(text, "{byte:02x}").ok()?;
+                }
+            }
+            Identifier::Ipv4 => text = Ipv4Addr::from(<[u8; 4]>::try_from(bytes).ok()?).to_string(),
+            Identifier::Ipv6 => {
+                text = Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?).to_string();
+            }
+            Identifier::Mac => {
+                text = bytes
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<Vec<_>>()
+                    .join(":");
+            }
+            Identifier::Port => {
+                text = u16::from_be_bytes(<[u8; 2]>::try_from(bytes).ok()?).to_string();
+            }
+        }
+        Some(text)
+    }
+
+    /// Bytes of the value written in `text`
+    /// # Errors
+    /// Fails if the text is not a value of the kind
+    pub(crate) fn encode(self, text: &str) -> Result<Vec<u8>, String> {
+        let invalid = || format!("invalid {}: {}", self.name(), text.trim());
+        let text = text.trim();
+        match self {
+            Identifier::GuidMicrosoft | Identifier::GuidRfc => {
+                let inner = text
+                    .strip_prefix('{')
+                    .and_then(|inner| inner.strip_suffix('}'))
+                    .unwrap_or(text);
+                let mut bytes = parse_hex_groups(inner, &['-'], 16).ok_or_else(invalid)?;
+                if self == Identifier::GuidMicrosoft {
+                    Self::swap_guid_fields(&mut bytes);
+                }
+                Ok(bytes)
+            }
+            Identifier::Ipv4 => text
+                .parse::<Ipv4Addr>()
+                .map(|ip| ip.octets().to_vec())
+                .map_err(|_| invalid()),
+            Identifier::Ipv6 => text
+                .parse::<Ipv6Addr>()
+                .map(|ip| ip.octets().to_vec())
+                .map_err(|_| invalid()),
+            Identifier::Mac => {
+                if text.split([':', '-']).any(|group| group.len() != 2) {
+                    return Err(invalid());
+                }
+                parse_hex_groups(text, &[':', '-'], 6).ok_or_else(invalid)
+            }
+            Identifier::Port => text
+                .parse::<u16>()
+                .map(|port| port.to_be_bytes().to_vec())
+                .map_err(|_| invalid()),
+        }
+    }
+
+    /// Reverse the three first fields of a GUID
+    fn swap_guid_fields(bytes: &mut [u8]) {
+        bytes[0..4].reverse();
+        bytes[4..6].reverse();
+        bytes[6..8].reverse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Identifier, OUI_VENDORS, mac_vendor};
+
+    #[test]
+    fn test_guid() {
+        let bytes = [
+            0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD,
+            0xEE, 0xFF,
+        ];
+        let text = "00112233-4455-6677-8899-aabbccddeeff";
+        assert_eq!(
+            Identifier::GuidMicrosoft.decode(&bytes).as_deref(),
+            Some(text)
+        );
+        assert_eq!(
+            Identifier::GuidMicrosoft.encode("{00112233-4455-6677-8899-AABBCCDDEEFF}"),
+            Ok(bytes.to_vec())
+        );
+        let rfc = Identifier::GuidRfc.encode(text).unwrap_or_default();
+        assert_eq!(rfc[..4], [0x00, 0x11, 0x22, 0x33]);
+        assert_eq!(Identifier::GuidRfc.decode(&rfc).as_deref(), Some(text));
+        assert!(Identifier::GuidRfc.encode("0011").is_err());
+    }
+
+    #[test]
+    fn test_network() {
+        assert_eq!(
+            Identifier::Ipv4.decode(&[192, 168, 0, 1]).as_deref(),
+            Some("192.168.0.1")
+        );
+        assert_eq!(Identifier::Ipv4.decode(&[192, 168, 0]), None);
+        let mut ipv6 = [0; 16];
+        ipv6[0] = 0x20;
+        ipv6[1] = 0x01;
+        ipv6[15] = 1;
+        assert_eq!(Identifier::Ipv6.decode(&ipv6).as_deref(), Some("2001::1"));
+        assert_eq!(Identifier::Ipv6.encode("2001::1"), Ok(ipv6.to_vec()));
+        let mac = [0x08, 0x00, 0x27, 0xAB, 0xCD, 0xEF];
+        assert_eq!(
+            Identifier::Mac.decode(&mac).as_deref(),
+            Some("08:00:27:ab:cd:ef")
+        );
+        assert_eq!(
+            Identifier::Mac.encode("08-00-27-AB-CD-EF"),
+            Ok(mac.to_vec())
+        );
+        assert!(Identifier::Mac.encode("080:027:ABC:DEF").is_err());
+        assert_eq!(mac_vendor(&mac), Some("Oracle VirtualBox"));
+        assert_eq!(mac_vendor(&[0xFF; 6]), None);
+        assert_eq!(
+            Identifier::Port.decode(&[0x01, 0xBB]).as_deref(),
+            Some("443")
+        );
+        assert_eq!(Identifier::Port.encode("443"), Ok(vec![0x01, 0xBB]));
+    }
+
+    #[test]
+    fn test_oui_vendors_sorted() {
+        // `mac_vendor` binary searches the table
+        assert!(OUI_VENDORS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+}
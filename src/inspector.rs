@@ -0,0 +1,690 @@
+//! Data inspector
+
+use bladvak::{
+    ErrorManager,
+    app::BladvakPanel,
+    eframe::egui::{self, Color32, Key, RichText, TextEdit},
+};
+
+use crate::{WombatApp, history::Edit};
+
+/// Fixed size number
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Numeric {
+    /// signed 8 bits
+    I8,
+    /// unsigned 8 bits
+    U8,
+    /// signed 16 bits
+    I16,
+    /// unsigned 16 bits
+    U16,
+    /// signed 32 bits
+    I32,
+    /// unsigned 32 bits
+    U32,
+    /// signed 64 bits
+    I64,
+    /// unsigned 64 bits
+    U64,
+    /// half precision float
+    F16,
+    /// single precision float
+    F32,
+    /// double precision float
+    F64,
+}
+
+impl Numeric {
+    /// Every number type
+    const ALL: [Numeric; 11] = [
+        Numeric::I8,
+        Numeric::U8,
+        Numeric::I16,
+        Numeric::U16,
+        Numeric::I32,
+        Numeric::U32,
+        Numeric::I64,
+        Numeric::U64,
+        Numeric::F16,
+        Numeric::F32,
+        Numeric::F64,
+    ];
+
+    /// Name of the type
+    fn name(self) -> &'static str {
+        match self {
+            Numeric::I8 => "i8",
+            Numeric::U8 => "u8",
+            Numeric::I16 => "i16",
+            Numeric::U16 => "u16",
+            Numeric::I32 => "i32",
+            Numeric::U32 => "u32",
+            Numeric::I64 => "i64",
+            Numeric::U64 => "u64",
+            Numeric::F16 => "f16",
+            Numeric::F32 => "f32",
+            Numeric::F64 => "f64",
+        }
+    }
+
+    /// Size in bytes
+    fn size(self) -> usize {
+        match self {
+            Numeric::I8 | Numeric::U8 => 1,
+            Numeric::I16 | Numeric::U16 | Numeric::F16 => 2,
+            Numeric::I32 | Numeric::U32 | Numeric::F32 => 4,
+            Numeric::I64 | Numeric::U64 | Numeric::F64 => 8,
+        }
+    }
+
+    /// Text of the value stored in `bytes`
+    fn decode(self, bytes: &[u8], little_endian: bool) -> Option<String> {
+        let mut buf = [0u8; 8];
+        let bytes = bytes.get(..self.size())?;
+        // numbers are read in little endian
+        for (idx, byte) in bytes.iter().enumerate() {
+            let idx = if little_endian {
+                idx
+            } else {
+                self.size() - 1 - idx
+            };
+            buf[idx] = *byte;
+        }
+        let value = u64::from_le_bytes(buf);
+        let [b0, b1, b2, b3, ..] = buf;
+        Some(match self {
+            Numeric::I8 => i8::from_le_bytes([b0]).to_string(),
+            Numeric::U8 => b0.to_string(),
+            Numeric::I16 => i16::from_le_bytes([b0, b1]).to_string(),
+            Numeric::U16 => u16::from_le_bytes([b0, b1]).to_string(),
+            Numeric::I32 => i32::from_le_bytes([b0, b1, b2, b3]).to_string(),
+            Numeric::U32 => u32::from_le_bytes([b0, b1, b2, b3]).to_string(),
+            Numeric::I64 => value.cast_signed().to_string(),
+            Numeric::U64 => value.to_string(),
+            Numeric::F16 => f16_to_f32(u16::from_le_bytes([b0, b1])).to_string(),
+            Numeric::F32 => f32::from_le_bytes([b0, b1, b2, b3]).to_string(),
+            Numeric::F64 => f64::from_bits(value).to_string(),
+        })
+    }
+
+    /// Bytes of the value written in `text`
+    /// # Errors
+    /// Fails if the text is not a number of the type
+    fn encode(self, text: &str, little_endian: bool) -> Result<Vec<u8>, String> {
+        let mut bytes = match self {
+            Numeric::F16 => f32_to_f16(parse_float(text)?).to_le_bytes().to_vec(),
+            #[allow(clippy::cast_possible_truncation)]
+            Numeric::F32 => (parse_float(text)? as f32).to_le_bytes().to_vec(),
+            Numeric::F64 => parse_float(text)?.to_le_bytes().to_vec(),
+            _ => {
+                let value = parse_integer(text)?;
+                let bits = self.size() * 8;
+                let (min, max) = if matches!(
+                    self,
+                    Numeric::I8 | Numeric::I16 | Numeric::I32 | Numeric::I64
+                ) {
+                    (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1)
+                } else {
+                    (0, (1i128 << bits) - 1)
+                };
+                if !(min..=max).contains(&value) {
+                    return Err(format!("{} must be between {min} and {max}", self.name()));
+                }
+                value.to_le_bytes()[..self.size()].to_vec()
+            }
+        };
+        if !little_endian {
+            bytes.reverse();
+        }
+        Ok(bytes)
+    }
+}
+
+/// Variable length integer
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Varint {
+    /// unsigned LEB128, also the protobuf varint
+    Uleb128,
+    /// signed LEB128
+    Sleb128,
+    /// protobuf zigzag varint (sint64)
+    Zigzag,
+}
+
+impl Varint {
+    /// Every varint
+    const ALL: [Varint; 3] = [Varint::Uleb128, Varint::Sleb128, Varint::Zigzag];
+
+    /// Longest encoding of a 64 bits value
+    const MAX_LEN: usize = 10;
+
+    /// Name of the varint
+    fn name(self) -> &'static str {
+        match self {
+            Varint::Uleb128 => "ULEB128 / varint",
+            Varint::Sleb128 => "SLEB128",
+            Varint::Zigzag => "Protobuf zigzag",
+        }
+    }
+
+    /// Text of the value encoded at the start of `bytes` and the encoded length
+    fn decode(self, bytes: &[u8]) -> Option<(String, usize)> {
+        let mut value = 0u128;
+        for (idx, byte) in bytes.iter().take(Self::MAX_LEN).enumerate() {
+            value |= u128::from(byte & 0x7F) << (7 * idx);
+            if byte & 0x80 == 0 {
+                let len = idx + 1;
+                let text = match self {
+                    Varint::Uleb128 => u64::try_from(value).ok()?.to_string(),
+                    Varint::Sleb128 => {
+                        let bits = 7 * len;
+                        let value = if byte & 0x40 != 0 {
+                            value.cast_signed() - (1i128 << bits)
+                        } else {
+                            value.cast_signed()
+                        };
+                        i64::try_from(value).ok()?.to_string()
+                    }
+                    Varint::Zigzag => {
+                        let value = u64::try_from(value).ok()?;
+                        ((value >> 1).cast_signed() ^ -(value & 1).cast_signed()).to_string()
+                    }
+                };
+                return Some((text, len));
+            }
+        }
+        None
+    }
+
+    /// Encoding of the value written in `text`
+    /// # Errors
+    /// Fails if the text is not a 64 bits integer
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    fn encode(self, text: &str) -> Result<Vec<u8>, String> {
+        let value = parse_integer(text)?;
+        let mut bytes = Vec::new();
+        match self {
+            Varint::Uleb128 | Varint::Zigzag => {
+                let mut value = if self == Varint::Zigzag {
+                    let value = i64::try_from(value).map_err(|_| "value must fit in i64")?;
+                    ((value << 1) ^ (value >> 63)).cast_unsigned()
+                } else {
+                    u64::try_from(value).map_err(|_| "value must fit in u64")?
+                };
+                loop {
+                    let byte = (value & 0x7F) as u8;
+                    value >>= 7;
+                    if value == 0 {
+                        bytes.push(byte);
+                        break;
+                    }
+                    bytes.push(byte | 0x80);
+                }
+            }
+            Varint::Sleb128 => {
+                let mut value = i64::try_from(value).map_err(|_| "value must fit in i64")?;
+                loop {
+                    let byte = (value & 0x7F) as u8;
+                    value >>= 7;
+                    if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+                        bytes.push(byte);
+                        break;
+                    }
+                    bytes.push(byte | 0x80);
+                }
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+/// Fixed length string
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TextKind {
+    /// ASCII, other bytes shown as dots
+    Ascii,
+    /// UTF-8
+    Utf8,
+    /// UTF-16 little endian
+    Utf16Le,
+    /// UTF-16 big endian
+    Utf16Be,
+}
+
+impl TextKind {
+    /// Every string kind
+    const ALL: [TextKind; 4] = [
+        TextKind::Ascii,
+        TextKind::Utf8,
+        TextKind::Utf16Le,
+        TextKind::Utf16Be,
+    ];
+
+    /// Name of the kind
+    fn name(self) -> &'static str {
+        match self {
+            TextKind::Ascii => "ASCII",
+            TextKind::Utf8 => "UTF-8",
+            TextKind::Utf16Le => "UTF-16 LE",
+            TextKind::Utf16Be => "UTF-16 BE",
+        }
+    }
+
+    /// Text of `bytes`
+    fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            TextKind::Ascii => bytes
+                .iter()
+                .map(|byte| {
+                    if byte.is_ascii_graphic() || *byte == b' ' {
+                        char::from(*byte)
+                    } else {
+                        '.'
+                    }
+                })
+                .collect(),
+            TextKind::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            TextKind::Utf16Le | TextKind::Utf16Be => {
+                let units = bytes
+                    .chunks_exact(2)
+                    .map(|pair| {
+                        let pair = [pair[0], pair[1]];
+                        if self == TextKind::Utf16Le {
+                            u16::from_le_bytes(pair)
+                        } else {
+                            u16::from_be_bytes(pair)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                String::from_utf16_lossy(&units)
+            }
+        }
+    }
+
+    /// `len` bytes of `text`, padded with zeros
+    /// # Errors
+    /// Fails if the text does not fit or is not ASCII
+    fn encode(self, text: &str, len: usize) -> Result<Vec<u8>, String> {
+        let mut bytes = match self {
+            TextKind::Ascii if !text.is_ascii() => return Err("text is not ASCII".to_string()),
+            TextKind::Ascii | TextKind::Utf8 => text.as_bytes().to_vec(),
+            TextKind::Utf16Le => text.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            TextKind::Utf16Be => text.encode_utf16().flat_map(u16::to_be_bytes).collect(),
+        };
+        if bytes.len() > len {
+            return Err(format!(
+                "text needs {} bytes, the length is {len}",
+                bytes.len()
+            ));
+        }
+        bytes.resize(len, 0);
+        Ok(bytes)
+    }
+}
+
+/// Decoded value of the inspector
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    /// number, little endian when set
+    Number(Numeric, bool),
+    /// variable length integer
+    Varint(Varint),
+    /// fixed length string
+    Text(TextKind),
+}
+
+/// Parse a decimal or `0x` hexadecimal integer
+/// # Errors
+/// Fails if the text is not an integer
+fn parse_integer(text: &str) -> Result<i128, String> {
+    let text = text.trim();
+    let (negative, digits) = text
+        .strip_prefix('-')
+        .map_or((false, text), |digits| (true, digits));
+    let value = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => i128::from_str_radix(hex, 16),
+        None => digits.parse::<i128>(),
+    }
+    .map_err(|_| format!("invalid integer: {text}"))?;
+    Ok(if negative { -value } else { value })
+}
+
+/// Parse a float
+/// # Errors
+/// Fails if the text is not a float
+fn parse_float(text: &str) -> Result<f64, String> {
+    text.trim()
+        .parse::<f64>()
+        .map_err(|_| format!("invalid float: {}", text.trim()))
+}
+
+/// Half precision float to single precision
+fn f16_to_f32(bits: u16) -> f32 {
+    let exponent = (bits >> 10) & 0x1F;
+    let mantissa = bits & 0x3FF;
+    let magnitude = match exponent {
+        // subnormal, mantissa * 2^-24
+        0 => f32::from(mantissa) / 16_777_216.0,
+        0x1F if mantissa == 0 => f32::INFINITY,
+        0x1F => f32::NAN,
+        _ => f32::from_bits(((u32::from(exponent) + 112) << 23) | (u32::from(mantissa) << 13)),
+    };
+    if bits & 0x8000 == 0 {
+        magnitude
+    } else {
+        -magnitude
+    }
+}
+
+/// Single precision float to half precision, rounded to nearest even
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+#[allow(clippy::cast_possible_wrap)]
+fn f32_to_f16(value: f64) -> u16 {
+    let bits = (value as f32).to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xFF) as i32;
+    let mantissa = bits & 0x7F_FFFF;
+    if exponent == 0xFF {
+        let nan = if mantissa == 0 { 0 } else { 0x200 };
+        return sign | 0x7C00 | nan;
+    }
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1F {
+        return sign | 0x7C00;
+    }
+    if half_exponent < -10 {
+        return sign;
+    }
+    // shift of the mantissa, with the implicit bit for subnormals
+    let (mantissa, shift, base) = if half_exponent <= 0 {
+        (mantissa | 0x80_0000, (14 - half_exponent) as u32, 0)
+    } else {
+        (mantissa, 13, (half_exponent as u32) << 10)
+    };
+    let half_mantissa = mantissa >> shift;
+    let remainder = mantissa & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    let round_up = remainder > halfway || (remainder == halfway && half_mantissa & 1 == 1);
+    // a carry of the mantissa moves to the exponent
+    sign | (base + half_mantissa + u32::from(round_up)) as u16
+}
+
+/// Inspector state
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub(crate) struct Inspector {
+    /// bytes of the strings
+    string_len: usize,
+    /// field being edited and its text
+    #[serde(skip)]
+    editing: Option<(Field, String)>,
+    /// error of the last edit
+    #[serde(skip)]
+    error: Option<String>,
+}
+
+impl Default for Inspector {
+    fn default() -> Self {
+        Self {
+            string_len: 16,
+            editing: None,
+            error: None,
+        }
+    }
+}
+
+impl Inspector {
+    /// Text of a field and its length in bytes
+    fn decode(&self, field: Field, bytes: &[u8]) -> Option<(String, usize)> {
+        match field {
+            Field::Number(numeric, little_endian) => numeric
+                .decode(bytes, little_endian)
+                .map(|text| (text, numeric.size())),
+            Field::Varint(varint) => varint.decode(bytes),
+            Field::Text(kind) => {
+                let bytes = &bytes[..bytes.len().min(self.string_len)];
+                Some((kind.decode(bytes), bytes.len()))
+            }
+        }
+    }
+
+    /// Bytes of a field replacing the start of `bytes`
+    /// # Errors
+    /// Fails if the text is not a value of the field
+    fn encode(&self, field: Field, text: &str, bytes: &[u8]) -> Result<Vec<u8>, String> {
+        match field {
+            Field::Number(numeric, little_endian) => numeric.encode(text, little_endian),
+            Field::Varint(varint) => varint.encode(text),
+            // near the end of the file the string is shorter, as when decoded
+            Field::Text(kind) => kind.encode(text, bytes.len().min(self.string_len)),
+        }
+    }
+
+    /// Editable value of a field, returns the text to write when the edit is validated
+    fn ui_field(&mut self, ui: &mut egui::Ui, field: Field, bytes: &[u8]) -> Option<String> {
+        let Some((decoded, _)) = self.decode(field, bytes) else {
+            ui.label("-");
+            return None;
+        };
+        let mut text = match &self.editing {
+            Some((editing, text)) if *editing == field => text.clone(),
+            _ => decoded,
+        };
+        let resp = ui.add(TextEdit::singleline(&mut text).desired_width(160.0));
+        if resp.changed() {
+            self.editing = Some((field, text.clone()));
+        }
+        if resp.lost_focus() {
+            let edited = self.editing.take().filter(|(editing, _)| *editing == field);
+            if edited.is_some() && ui.input(|i| i.key_pressed(Key::Enter)) {
+                return Some(text);
+            }
+        }
+        None
+    }
+
+    /// Show the decoded values of `bytes`, returns the field to write and its bytes
+    fn ui(&mut self, ui: &mut egui::Ui, bytes: &[u8]) -> Option<(Field, String)> {
+        let mut write = None;
+        egui::Grid::new("inspector_numbers")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Type");
+                ui.label("Little endian");
+                ui.label("Big endian");
+                ui.end_row();
+                for numeric in Numeric::ALL {
+                    ui.label(numeric.name());
+                    for little_endian in [true, false] {
+                        let field = Field::Number(numeric, little_endian);
+                        if let Some(text) = self.ui_field(ui, field, bytes) {
+                            write = Some((field, text));
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+        ui.separator();
+        egui::Grid::new("inspector_varints")
+            .striped(true)
+            .show(ui, |ui| {
+                for varint in Varint::ALL {
+                    let field = Field::Varint(varint);
+                    ui.label(varint.name());
+                    if let Some(text) = self.ui_field(ui, field, bytes) {
+                        write = Some((field, text));
+                    }
+                    if let Some((_, len)) = self.decode(field, bytes) {
+                        ui.label(format!("{len} bytes"));
+                    }
+                    ui.end_row();
+                }
+            });
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("String length");
+            ui.add(egui::DragValue::new(&mut self.string_len).range(1..=256));
+        });
+        egui::Grid::new("inspector_strings")
+            .striped(true)
+            .show(ui, |ui| {
+                for kind in TextKind::ALL {
+                    let field = Field::Text(kind);
+                    ui.label(kind.name());
+                    if let Some(text) = self.ui_field(ui, field, bytes) {
+                        write = Some((field, text));
+                    }
+                    ui.end_row();
+                }
+            });
+        write
+    }
+}
+
+/// Data inspector panel
+#[derive(Debug)]
+pub(crate) struct PanelInspector;
+
+impl BladvakPanel for PanelInspector {
+    type App = WombatApp;
+    fn name(&self) -> &'static str {
+        "Data inspector"
+    }
+    fn has_ui(&self) -> bool {
+        true
+    }
+    fn has_settings(&self) -> bool {
+        false
+    }
+    fn ui(&self, app: &mut WombatApp, ui: &mut egui::Ui, _error_manager: &mut ErrorManager) {
+        let offset = app
+            .selection
+            .range
+            .map(|(start, _)| start)
+            .or(app.selection.cursor)
+            .filter(|offset| *offset < app.binary_file.len());
+        let Some(offset) = offset else {
+            ui.label("No selection");
+            return;
+        };
+        let offset_format = &app.display_settings.offset_format;
+        ui.label(format!(
+            "Values at {}",
+            offset_format.format(offset, offset_format.digits(app.binary_file.len()))
+        ));
+        ui.label("Press Enter to write an edited value");
+        let bytes = &app.binary_file[offset..];
+        let Some((field, text)) = app.inspector.ui(ui, bytes) else {
+            if let Some(err) = &app.inspector.error {
+                ui.label(RichText::new(err).color(Color32::LIGHT_RED));
+            }
+            return;
+        };
+        let encoded = app
+            .inspector
+            .encode(field, &text, bytes)
+            .and_then(|encoded| {
+                let (_, len) = app
+                    .inspector
+                    .decode(field, bytes)
+                    .ok_or("no value to replace")?;
+                Ok((encoded, len))
+            });
+        match encoded {
+            Ok((encoded, len)) => {
+                app.inspector.error = None;
+                let edit = Edit::overwrite_range(&app.binary_file, offset..offset + len, encoded);
+                if let Err(err) = app.apply_edit(edit) {
+                    app.inspector.error = Some(err.to_string());
+                }
+            }
+            Err(err) => app.inspector.error = Some(err),
+        }
+    }
+
+    fn ui_settings(
+        &self,
+        _app: &mut WombatApp,
+        _ui: &mut egui::Ui,
+        _error_manager: &mut ErrorManager,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Field, Inspector, Numeric, TextKind, Varint, f16_to_f32, f32_to_f16};
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_f16() {
+        for (bits, value) in [
+            (0x3C00, 1.0),
+            (0xC000, -2.0),
+            (0x7BFF, 65504.0),
+            (0x0001, 5.960_464_5e-8),
+            (0x7C00, f32::INFINITY),
+        ] {
+            assert_eq!(f16_to_f32(bits), value);
+            assert_eq!(f32_to_f16(f64::from(value)), bits);
+        }
+        // rounded to nearest even
+        assert_eq!(f32_to_f16(1.000_488_281_25), 0x3C00);
+        assert_eq!(f32_to_f16(65520.0), 0x7C00);
+    }
+
+    #[test]
+    fn test_numbers() {
+        let bytes = [0xFE, 0xFF, 0x00, 0x00];
+        assert_eq!(Numeric::I16.decode(&bytes, true).as_deref(), Some("-2"));
+        assert_eq!(Numeric::U16.decode(&bytes, false).as_deref(), Some("65279"));
+        assert_eq!(Numeric::I8.decode(&[], true), None);
+        assert_eq!(Numeric::I16.encode("-2", false), Ok(vec![0xFF, 0xFE]));
+        assert_eq!(Numeric::U32.encode("0x10", true), Ok(vec![0x10, 0, 0, 0]));
+        assert!(Numeric::U8.encode("256", true).is_err());
+        assert!(Numeric::I8.encode("-129", true).is_err());
+        assert_eq!(Numeric::F32.encode("1", false), Ok(vec![0x3F, 0x80, 0, 0]));
+    }
+
+    #[test]
+    fn test_varints() {
+        let uleb = [0xE5, 0x8E, 0x26, 0xFF];
+        assert_eq!(
+            Varint::Uleb128.decode(&uleb),
+            Some(("624485".to_string(), 3))
+        );
+        assert_eq!(Varint::Uleb128.encode("624485"), Ok(uleb[..3].to_vec()));
+        let sleb = [0xC0, 0xBB, 0x78];
+        assert_eq!(
+            Varint::Sleb128.decode(&sleb),
+            Some(("-123456".to_string(), 3))
+        );
+        assert_eq!(Varint::Sleb128.encode("-123456"), Ok(sleb.to_vec()));
+        assert_eq!(Varint::Zigzag.decode(&[0x03]), Some(("-2".to_string(), 1)));
+        assert_eq!(Varint::Zigzag.encode("-2"), Ok(vec![0x03]));
+        assert_eq!(Varint::Uleb128.decode(&[0x80, 0x80]), None);
+    }
+
+    #[test]
+    fn test_strings() {
+        assert_eq!(TextKind::Ascii.decode(b"ab\0c"), "ab.c");
+        assert_eq!(TextKind::Utf16Le.decode(b"h\0i\0"), "hi");
+        assert_eq!(
+            TextKind::Utf16Be.encode("hi", 6),
+            Ok(b"\0h\0i\0\0".to_vec())
+        );
+        assert!(TextKind::Utf8.encode("wombat", 4).is_err());
+        assert!(TextKind::Ascii.encode("é", 4).is_err());
+        // the string is cut by the end of the file
+        let inspector = Inspector::default();
+        let field = Field::Text(TextKind::Ascii);
+        assert_eq!(inspector.encode(field, "ab", b"xyz"), Ok(b"ab\0".to_vec()));
+        assert_eq!(inspector.decode(field, b"xyz").map(|(_, len)| len), Some(3));
+    }
+}
@@ -21,9 +21,27 @@
 #![warn(clippy::multiple_crate_versions)]
 
 mod app;
+mod bits;
+mod bookmarks;
 mod central_panel;
+mod data_source;
+mod document;
+mod editor;
+mod history;
+mod identifiers;
+mod inspector;
+mod literals;
+mod minimap;
+mod offsets;
+mod open;
+mod palette;
 mod panels;
+mod save;
 mod selection;
+mod split_view;
+mod text_decoder;
+mod timestamps;
 mod windows;
+mod words;
 
 pub use app::WombatApp;
@@ -0,0 +1,335 @@
+//! Selection as source code literals
+
+use bladvak::eframe::egui;
+
+/// Base64 alphabet
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard padded base64 of `bytes`
+fn base64(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (idx, byte)| {
+            group | (u32::from(*byte) << (16 - 8 * idx))
+        });
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                let sextet = (group >> (18 - 6 * idx)) & 0x3F;
+                text.push(char::from(BASE64[sextet as usize]));
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+/// Lines of `width` items, separated by commas
+fn array_lines(
+    bytes: &[u8],
+    width: usize,
+    indent: &str,
+    trailing_comma: bool,
+    item: impl Fn(u8) -> String,
+) -> String {
+    let mut text = bytes
+        .chunks(width.max(1))
+        .map(|line| {
+            let items = line.iter().map(|byte| item(*byte)).collect::<Vec<_>>();
+            format!("{indent}{}", items.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+    if trailing_comma && !bytes.is_empty() {
+        text.push(',');
+    }
+    text
+}
+
+/// Language or encoding of an exported selection
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) enum LiteralFormat {
+    /// C `unsigned char[]`
+    C,
+    /// Rust `[u8; N]`
+    RustArray,
+    /// Rust `&[u8]`
+    RustSlice,
+    /// Python `bytes`
+    Python,
+    /// Go `[]byte`
+    Go,
+    /// Java `byte[]`
+    Java,
+    /// JavaScript `Uint8Array`
+    JavaScript,
+    /// C# `byte[]`
+    CSharp,
+    /// JSON array of numbers
+    Json,
+    /// base64
+    Base64,
+    /// `\x` escaped string
+    Escaped,
+}
+
+impl LiteralFormat {
+    /// Every format
+    const ALL: [LiteralFormat; 11] = [
+        LiteralFormat::C,
+        LiteralFormat::RustArray,
+        LiteralFormat::RustSlice,
+        LiteralFormat::Python,
+        LiteralFormat::Go,
+        LiteralFormat::Java,
+        LiteralFormat::JavaScript,
+        LiteralFormat::CSharp,
+        LiteralFormat::Json,
+        LiteralFormat::Base64,
+        LiteralFormat::Escaped,
+    ];
+
+    /// Name of the format
+    fn name(self) -> &'static str {
+        match self {
+            LiteralFormat::C => "C unsigned char[]",
+            LiteralFormat::RustArray => "Rust [u8; N]",
+            LiteralFormat::RustSlice => "Rust &[u8]",
+            LiteralFormat::Python => "Python bytes",
+            LiteralFormat::Go => "Go []byte",
+            LiteralFormat::Java => "Java byte[]",
+            LiteralFormat::JavaScript => "JavaScript Uint8Array",
+            LiteralFormat::CSharp => "C# byte[]",
+            LiteralFormat::Json => "JSON array",
+            LiteralFormat::Base64 => "Base64",
+            LiteralFormat::Escaped => "\\x escaped string",
+        }
+    }
+
+    /// Extension of an exported file
+    fn extension(self) -> &'static str {
+        match self {
+            LiteralFormat::C => "c",
+            LiteralFormat::RustArray | LiteralFormat::RustSlice => "rs",
+            LiteralFormat::Python => "py",
+            LiteralFormat::Go => "go",
+            LiteralFormat::Java => "java",
+            LiteralFormat::JavaScript => "js",
+            LiteralFormat::CSharp => "cs",
+            LiteralFormat::Json => "json",
+            LiteralFormat::Base64 | LiteralFormat::Escaped => "txt",
+        }
+    }
+
+    /// Does the format use a variable name
+    fn has_name(self) -> bool {
+        !matches!(
+            self,
+            LiteralFormat::Json | LiteralFormat::Base64 | LiteralFormat::Escaped
+        )
+    }
+
+    /// Literal of `bytes` named `name`, with `width` bytes per line
+    fn format(self, bytes: &[u8], name: &str, width: usize) -> String {
+        let hex = |byte: u8| format!("0x{byte:02X}");
+        let len = bytes.len();
+        match self {
+            LiteralFormat::C => format!(
+                "unsigned char {name}[{len}] = {{\n{}\n}};\n",
+                array_lines(bytes, width, "    ", true, hex)
+            ),
+            LiteralFormat::RustArray => format!(
+                "let {name}: [u8; {len}] = [\n{}\n];\n",
+                array_lines(bytes, width, "    ", true, hex)
+            ),
+            LiteralFormat::RustSlice => format!(
+                "let {name}: &[u8] = &[\n{}\n];\n",
+                array_lines(bytes, width, "    ", true, hex)
+            ),
+            LiteralFormat::Python => format!(
+                "{name} = bytes([\n{}\n])\n",
+                array_lines(bytes, width, "    ", true, hex)
+            ),
+            LiteralFormat::Go => format!(
+                "{name} := []byte{{\n{}\n}}\n",
+                array_lines(bytes, width, "\t", true, hex)
+            ),
+            LiteralFormat::Java => format!(
+                "byte[] {name} = {{\n{}\n}};\n",
+                // java bytes are signed
+                array_lines(bytes, width, "    ", true, |byte| {
+                    if byte > 0x7F {
+                        format!("(byte) 0x{byte:02X}")
+                    } else {
+                        hex(byte)
+                    }
+                })
+            ),
+            LiteralFormat::JavaScript => format!(
+                "const {name} = new Uint8Array([\n{}\n]);\n",
+                array_lines(bytes, width, "    ", true, hex)
+            ),
+            LiteralFormat::CSharp => format!(
+                "byte[] {name} = {{\n{}\n}};\n",
+                array_lines(bytes, width, "    ", true, hex)
+            ),
+            LiteralFormat::Json => format!(
+                "[\n{}\n]\n",
+                array_lines(bytes, width, "  ", false, |byte| byte.to_string())
+            ),
+            LiteralFormat::Base64 => {
+                // whole groups of 3 bytes per line
+                let line_bytes = width.max(1).div_ceil(3) * 3;
+                let lines = bytes.chunks(line_bytes).map(base64).collect::<Vec<_>>();
+                format!("{}\n", lines.join("\n"))
+            }
+            LiteralFormat::Escaped => {
+                let lines = bytes
+                    .chunks(width.max(1))
+                    .map(|line| {
+                        let escaped = line
+                            .iter()
+                            .map(|byte| format!("\\x{byte:02X}"))
+                            .collect::<Vec<_>>()
+                            .concat();
+                        format!("\"{escaped}\"")
+                    })
+                    .collect::<Vec<_>>();
+                format!("{}\n", lines.join("\n"))
+            }
+        }
+    }
+}
+
+/// `name` turned into an identifier valid in every language,
+/// other characters are replaced by `_` and a leading digit is prefixed with `_`
+fn variable_name(name: &str) -> String {
+    let name = name.trim();
+    if name.is_empty() {
+        return "data".to_string();
+    }
+    let mut identifier = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    if identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        identifier.insert(0, '_');
+    }
+    identifier
+}
+
+/// Settings of the exported literals
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub(crate) struct Literals {
+    /// language or encoding
+    format: LiteralFormat,
+    /// name of the variable
+    name: String,
+    /// bytes per line
+    width: usize,
+}
+
+impl Default for Literals {
+    fn default() -> Self {
+        Self {
+            format: LiteralFormat::C,
+            name: "data".to_string(),
+            width: 16,
+        }
+    }
+}
+
+impl Literals {
+    /// Literal of `bytes` with the current settings
+    pub(crate) fn format(&self, bytes: &[u8]) -> String {
+        self.format
+            .format(bytes, &variable_name(&self.name), self.width)
+    }
+
+    /// File name of an exported literal
+    pub(crate) fn file_name(&self) -> String {
+        format!("exported.{}", self.format.extension())
+    }
+
+    /// Name of the current format
+    pub(crate) fn format_name(&self) -> &'static str {
+        self.format.name()
+    }
+
+    /// Show the settings
+    pub(crate) fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::ComboBox::from_label("Literal")
+            .selected_text(self.format.name())
+            .show_ui(ui, |ui| {
+                for format in LiteralFormat::ALL {
+                    ui.selectable_value(&mut self.format, format, format.name());
+                }
+            });
+        if self.format.has_name() {
+            ui.horizontal(|ui| {
+                ui.label("Variable name");
+                ui.text_edit_singleline(&mut self.name);
+            });
+            let name = variable_name(&self.name);
+            if name != self.name.trim() {
+                ui.label(format!("Exported as {name}"));
+            }
+        }
+        ui.horizontal(|ui| {
+            ui.label("Bytes per line");
+            ui.add(egui::DragValue::new(&mut self.width).range(1..=1024));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LiteralFormat, base64, variable_name};
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b"Man"), "TWFu");
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"M"), "TQ==");
+        assert_eq!(base64(b""), "");
+        assert_eq!(
+            LiteralFormat::Base64.format(b"ManMan", "", 2),
+            "TWFu\nTWFu\n"
+        );
+    }
+
+    #[test]
+    fn test_literals() {
+        let bytes = [0x00, 0x7F, 0x80];
+        assert_eq!(
+            LiteralFormat::C.format(&bytes, "blob", 2),
+            "unsigned char blob[3] = {\n    0x00, 0x7F,\n    0x80,\n};\n"
+        );
+        assert_eq!(
+            LiteralFormat::Java.format(&bytes, "blob", 8),
+            "byte[] blob = {\n    0x00, 0x7F, (byte) 0x80,\n};\n"
+        );
+        assert_eq!(
+            LiteralFormat::Go.format(&bytes, "blob", 8),
+            "blob := []byte{\n\t0x00, 0x7F, 0x80,\n}\n"
+        );
+        assert_eq!(
+            LiteralFormat::Json.format(&bytes, "", 2),
+            "[\n  0, 127,\n  128\n]\n"
+        );
+        assert_eq!(
+            LiteralFormat::Escaped.format(&bytes, "", 2),
+            "\"\\x00\\x7F\"\n\"\\x80\"\n"
+        );
+    }
+
+    #[test]
+    fn test_variable_name() {
+        assert_eq!(variable_name(" blob "), "blob");
+        assert_eq!(variable_name("my data"), "my_data");
+        assert_eq!(variable_name("1blob"), "_1blob");
+        assert_eq!(variable_name("a-b;c"), "a_b_c");
+        assert_eq!(variable_name(""), "data");
+    }
+}
@@ -0,0 +1,338 @@
+//! Overview minimap of the file
+
+use bladvak::eframe::egui::{self, Color32, Sense, Stroke, StrokeKind, Theme};
+
+use crate::WombatApp;
+use crate::palette::{ByteClass, ByteColors};
+
+/// Value shown for each block of the minimap
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) enum MinimapMode {
+    /// Shannon entropy of the block
+    Entropy,
+    /// most frequent byte class of the block
+    ByteClass,
+}
+
+impl MinimapMode {
+    /// Every mode
+    const ALL: [MinimapMode; 2] = [MinimapMode::Entropy, MinimapMode::ByteClass];
+
+    /// Name of the mode
+    fn name(self) -> &'static str {
+        match self {
+            MinimapMode::Entropy => "Entropy",
+            MinimapMode::ByteClass => "Byte class",
+        }
+    }
+}
+
+/// Summary of a block of the file
+#[derive(Debug, Clone, Copy)]
+struct Block {
+    /// entropy, from 0 to 1
+    entropy: f32,
+    /// most frequent class
+    class: ByteClass,
+}
+
+/// Shannon entropy of `bytes`, from 0 to 1
+#[allow(clippy::cast_precision_loss)]
+fn entropy(bytes: &[u8]) -> f32 {
+    let mut counts = [0usize; 256];
+    for byte in bytes {
+        counts[usize::from(*byte)] += 1;
+    }
+    let total = bytes.len() as f32;
+    let bits: f32 = counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let p = *count as f32 / total;
+            -p * p.log2()
+        })
+        .sum();
+    bits / 8.0
+}
+
+/// Most frequent class of `bytes`
+fn dominant_class(bytes: &[u8]) -> ByteClass {
+    let mut counts = [0usize; ByteClass::ALL.len()];
+    for byte in bytes {
+        let class = ByteClass::of(*byte);
+        if let Some(idx) = ByteClass::ALL.iter().position(|c| *c == class) {
+            counts[idx] += 1;
+        }
+    }
+    std::iter::zip(ByteClass::ALL, counts)
+        .max_by_key(|(_, count)| *count)
+        .map_or(ByteClass::Null, |(class, _)| class)
+}
+
+/// Pixel rows covered by the sorted byte `ranges`, merged when they touch,
+/// for `rows` rows showing `file_len` bytes
+fn merge_rows(
+    ranges: impl IntoIterator<Item = (usize, usize)>,
+    file_len: usize,
+    rows: usize,
+) -> Vec<(usize, usize)> {
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in ranges {
+        let (first, last) = (start * rows / file_len, end * rows / file_len);
+        match merged.last_mut() {
+            Some((_, prev_last)) if first <= *prev_last + 1 => *prev_last = (*prev_last).max(last),
+            _ => merged.push((first, last)),
+        }
+    }
+    merged
+}
+
+/// Minimap settings and state
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub(crate) struct Minimap {
+    /// show the minimap
+    pub(crate) enabled: bool,
+    /// value shown for each block
+    mode: MinimapMode,
+    /// summary of each block, computed once per buffer
+    #[serde(skip)]
+    blocks: Vec<Block>,
+    /// bytes per block
+    #[serde(skip)]
+    block_size: usize,
+    /// first and last byte of the view, from the last frame
+    #[serde(skip)]
+    pub(crate) view: (usize, usize),
+    /// offset to bring to the top of the view
+    #[serde(skip)]
+    pub(crate) jump: Option<usize>,
+    /// distance from the top of the view to the byte grabbed by a drag
+    #[serde(skip)]
+    grab: Option<usize>,
+}
+
+impl Default for Minimap {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: MinimapMode::Entropy,
+            blocks: Vec::new(),
+            block_size: 1,
+            view: (0, 0),
+            jump: None,
+            grab: None,
+        }
+    }
+}
+
+impl Minimap {
+    /// Width of the minimap
+    pub(crate) const WIDTH: f32 = 28.0;
+    /// Number of blocks of the file
+    const BLOCKS: usize = 1024;
+    /// Bytes read per block, larger blocks are sampled
+    const SAMPLE: usize = 4096;
+
+    /// Forget the blocks of the previous buffer
+    pub(crate) fn reset(&mut self) {
+        self.blocks.clear();
+    }
+
+    /// Summarize the blocks of `data`
+    fn compute(&mut self, data: &[u8]) {
+        self.block_size = data.len().div_ceil(Self::BLOCKS).max(1);
+        self.blocks = data
+            .chunks(self.block_size)
+            .map(|chunk| {
+                let sample = &chunk[..chunk.len().min(Self::SAMPLE)];
+                Block {
+                    entropy: entropy(sample),
+                    class: dominant_class(sample),
+                }
+            })
+            .collect();
+    }
+
+    /// Color of the block holding `offset`
+    fn block_color(&self, offset: usize, byte_colors: &ByteColors, is_light: bool) -> Color32 {
+        let Some(block) = self.blocks.get(offset / self.block_size) else {
+            return Color32::TRANSPARENT;
+        };
+        match self.mode {
+            MinimapMode::Entropy => {
+                let (low, high) = if is_light {
+                    (
+                        Color32::from_rgb(220, 230, 255),
+                        Color32::from_rgb(200, 30, 30),
+                    )
+                } else {
+                    (
+                        Color32::from_rgb(20, 30, 70),
+                        Color32::from_rgb(255, 200, 40),
+                    )
+                };
+                low.lerp_to_gamma(high, block.entropy)
+            }
+            MinimapMode::ByteClass => byte_colors.class_color(block.class, is_light),
+        }
+    }
+
+    /// Show the minimap settings
+    pub(crate) fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.enabled, "Minimap");
+        if !self.enabled {
+            return;
+        }
+        egui::ComboBox::from_label("Minimap shows")
+            .selected_text(self.mode.name())
+            .show_ui(ui, |ui| {
+                for mode in MinimapMode::ALL {
+                    ui.selectable_value(&mut self.mode, mode, mode.name());
+                }
+            });
+    }
+}
+
+impl WombatApp {
+    /// Show the minimap, a drag or a click moves the view
+    #[allow(clippy::cast_precision_loss)]
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    pub(crate) fn ui_minimap(&mut self, ui: &mut egui::Ui) {
+        let file_len = self.binary_file.len();
+        if self.display_settings.minimap.blocks.is_empty() {
+            self.display_settings.minimap.compute(&self.binary_file);
+        }
+        let (rect, resp) = ui.allocate_exact_size(ui.available_size(), Sense::click_and_drag());
+        if file_len == 0 || rect.height() < 1.0 {
+            return;
+        }
+        let is_light = ui.ctx().theme() == Theme::Light;
+        let minimap = &self.display_settings.minimap;
+        let painter = ui.painter_at(rect);
+        let y_of = |offset: usize| rect.top() + offset as f32 / file_len as f32 * rect.height();
+        let band = |start: usize, end: usize, left: f32, width: f32, color: Color32| {
+            let top = y_of(start);
+            let bottom = y_of(end + 1).max(top + 2.0);
+            painter.rect_filled(
+                egui::Rect::from_min_max(
+                    egui::pos2(rect.left() + left, top),
+                    egui::pos2(rect.left() + left + width, bottom),
+                ),
+                0.0,
+                color,
+            );
+        };
+
+        // one row of pixels per step
+        let rows = rect.height().ceil() as usize;
+        for row in 0..rows {
+            let offset = row * file_len / rows;
+            band(
+                offset,
+                offset,
+                0.0,
+                rect.width(),
+                minimap.block_color(offset, &self.display_settings.byte_colors, is_light),
+            );
+        }
+
+        // search hits, bookmarks and selection, side by side
+        let column = rect.width() / 3.0;
+        let search_color = if is_light {
+            self.windows_data.search.color.0
+        } else {
+            self.windows_data.search.color.1
+        };
+        // there can be many more results than rows, paint one band per run of rows
+        let found = self.windows_data.search.matches();
+        let found = found.iter().map(|found| (found.start, found.end - 1));
+        for (first, last) in merge_rows(found, file_len, rows) {
+            let start = first * file_len / rows;
+            let end = ((last + 1) * file_len / rows).saturating_sub(1).max(start);
+            band(start, end, 0.0, column, search_color);
+        }
+        for bookmark in self.current_bookmarks() {
+            band(bookmark.start, bookmark.end, column, column, bookmark.color);
+        }
+        let selection_color = if is_light {
+            self.selection.color.0
+        } else {
+            self.selection.color.1
+        };
+        for (start, end) in self.selection.ranges() {
+            band(start, end, column * 2.0, column, selection_color);
+        }
+
+        // current view
+        let (view_start, view_end) = minimap.view;
+        let view_rect = egui::Rect::from_min_max(
+            egui::pos2(rect.left(), y_of(view_start)),
+            egui::pos2(rect.right(), y_of(view_end + 1).max(y_of(view_start) + 4.0)),
+        );
+        let view_color = ui.visuals().strong_text_color();
+        painter.rect_filled(view_rect, 1.0, view_color.gamma_multiply(0.2));
+        painter.rect_stroke(
+            view_rect,
+            1.0,
+            Stroke::new(1.5, view_color),
+            StrokeKind::Inside,
+        );
+
+        let Some(pos) = resp.interact_pointer_pos() else {
+            return;
+        };
+        let fraction = ((pos.y - rect.top()) / rect.height()).clamp(0.0, 1.0);
+        let pointed = ((fraction * file_len as f32) as usize).min(file_len - 1);
+        let minimap = &mut self.display_settings.minimap;
+        if resp.drag_started() {
+            // keep the grabbed byte under the pointer when the view is dragged
+            minimap.grab = view_rect
+                .contains(pos)
+                .then(|| pointed.saturating_sub(view_start));
+        }
+        if resp.clicked() || resp.drag_started() {
+            self.windows_data.goto.remember(self.selection.position());
+        }
+        if resp.clicked() || resp.dragged() {
+            let grab = minimap
+                .grab
+                .filter(|_| resp.dragged())
+                .unwrap_or((view_end - view_start) / 2);
+            minimap.jump = Some(pointed.saturating_sub(grab));
+        }
+        if resp.drag_stopped() {
+            minimap.grab = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dominant_class, entropy, merge_rows};
+    use crate::palette::ByteClass;
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_entropy() {
+        assert_eq!(entropy(&[0; 64]), 0.0);
+        assert_eq!(entropy(&[0, 1]), 0.125);
+        let all = (0..=255).collect::<Vec<u8>>();
+        assert_eq!(entropy(&all), 1.0);
+    }
+
+    #[test]
+    fn test_dominant_class() {
+        assert_eq!(dominant_class(b"text\0"), ByteClass::Printable);
+        assert_eq!(dominant_class(&[0, 0, 0xFF]), ByteClass::Null);
+    }
+
+    #[test]
+    fn test_merge_rows() {
+        let found = [(0, 1), (4, 5), (9, 9), (50, 59), (98, 99)];
+        assert_eq!(merge_rows(found, 100, 10), vec![(0, 0), (5, 5), (9, 9)]);
+        assert_eq!(merge_rows(found, 100, 100).len(), 5);
+        assert!(merge_rows([], 100, 10).is_empty());
+    }
+}
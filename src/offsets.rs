@@ -0,0 +1,151 @@
+//! Offset column
+
+use bladvak::eframe::egui;
+
+/// Radix of the displayed offsets
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) enum OffsetRadix {
+    /// base 16
+    Hex,
+    /// base 10
+    Decimal,
+    /// base 8
+    Octal,
+}
+
+impl OffsetRadix {
+    /// Every radix
+    const ALL: [OffsetRadix; 3] = [OffsetRadix::Hex, OffsetRadix::Decimal, OffsetRadix::Octal];
+
+    /// Name of the radix
+    fn name(self) -> &'static str {
+        match self {
+            OffsetRadix::Hex => "Hex",
+            OffsetRadix::Decimal => "Decimal",
+            OffsetRadix::Octal => "Octal",
+        }
+    }
+
+    /// Value of the radix
+    fn radix(self) -> u32 {
+        match self {
+            OffsetRadix::Hex => 16,
+            OffsetRadix::Decimal => 10,
+            OffsetRadix::Octal => 8,
+        }
+    }
+}
+
+/// Format of the offset column
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub(crate) struct OffsetFormat {
+    /// radix of the offsets
+    pub(crate) radix: OffsetRadix,
+    /// address of the first byte, added to every displayed offset
+    pub(crate) base_address: usize,
+}
+
+impl Default for OffsetFormat {
+    fn default() -> Self {
+        Self {
+            radix: OffsetRadix::Hex,
+            base_address: 0,
+        }
+    }
+}
+
+impl OffsetFormat {
+    /// Minimum number of digits of an address
+    const MIN_DIGITS: usize = 8;
+
+    /// Address of an offset
+    pub(crate) fn address(&self, offset: usize) -> usize {
+        self.base_address.saturating_add(offset)
+    }
+
+    /// Number of digits of the addresses of a file of `file_len` bytes
+    pub(crate) fn digits(&self, file_len: usize) -> usize {
+        let radix = self.radix.radix() as usize;
+        let mut last = self.address(file_len.saturating_sub(1));
+        let mut digits = 1;
+        while last >= radix {
+            last /= radix;
+            digits += 1;
+        }
+        digits.max(Self::MIN_DIGITS)
+    }
+
+    /// Address of an offset, padded to `digits`
+    pub(crate) fn format(&self, offset: usize, digits: usize) -> String {
+        let address = self.address(offset);
+        match self.radix {
+            OffsetRadix::Hex => format!("{address:0digits$X}"),
+            OffsetRadix::Decimal => format!("{address:0digits$}"),
+            OffsetRadix::Octal => format!("{address:0digits$o}"),
+        }
+    }
+
+    /// Parse an address written in the radix, returns its offset
+    pub(crate) fn parse(&self, input: &str) -> Option<usize> {
+        let input = input.trim();
+        let digits = match self.radix {
+            OffsetRadix::Hex => input.strip_prefix("0x").unwrap_or(input),
+            OffsetRadix::Decimal => input,
+            OffsetRadix::Octal => input.strip_prefix("0o").unwrap_or(input),
+        };
+        usize::from_str_radix(digits, self.radix.radix())
+            .ok()?
+            .checked_sub(self.base_address)
+    }
+
+    /// Show the offset settings
+    pub(crate) fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::ComboBox::from_label("Offset radix")
+            .selected_text(self.radix.name())
+            .show_ui(ui, |ui| {
+                for radix in OffsetRadix::ALL {
+                    ui.selectable_value(&mut self.radix, radix, radix.name());
+                }
+            });
+        ui.horizontal(|ui| {
+            ui.label("Base address 0x");
+            ui.add(egui::DragValue::new(&mut self.base_address).hexadecimal(8, false, true))
+                .on_hover_text("Address of the first byte, for memory dumps");
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OffsetFormat, OffsetRadix};
+
+    #[test]
+    fn test_offset_digits() {
+        let mut format = OffsetFormat::default();
+        assert_eq!(format.digits(0x100), 8);
+        format.base_address = 0xFFFF_FFF0;
+        assert_eq!(format.digits(0x10), 8);
+        assert_eq!(format.digits(0x11), 9);
+        format.radix = OffsetRadix::Decimal;
+        format.base_address = 0;
+        assert_eq!(format.digits(1_000_000_000), 9);
+    }
+
+    #[test]
+    fn test_offset_format_and_parse() {
+        let mut format = OffsetFormat {
+            radix: OffsetRadix::Hex,
+            base_address: 0x8000_0000,
+        };
+        assert_eq!(format.format(0x10, 8), "80000010");
+        assert_eq!(format.parse("0x80000010"), Some(0x10));
+        assert_eq!(format.parse("10"), None);
+        format.radix = OffsetRadix::Octal;
+        format.base_address = 0;
+        assert_eq!(format.format(8, 4), "0010");
+        assert_eq!(format.parse("10"), Some(8));
+        format.radix = OffsetRadix::Decimal;
+        assert_eq!(format.format(42, 8), "00000042");
+    }
+}
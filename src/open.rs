@@ -0,0 +1,53 @@
+//! Open files
+
+use bladvak::errors::AppError;
+use std::path::Path;
+
+use crate::WombatApp;
+use crate::data_source::DataSource;
+
+#[cfg(not(target_arch = "wasm32"))]
+use bladvak::{eframe::egui, errors::ErrorManager};
+
+impl WombatApp {
+    /// Open the file at `path` in a new tab, the file is mapped instead of read
+    /// # Errors
+    /// Fails if the file cannot be opened
+    pub(crate) fn open_path(&mut self, path: &Path) -> Result<(), AppError> {
+        let data = DataSource::open(path)?;
+        self.open_document(data, path.to_path_buf());
+        Ok(())
+    }
+
+    /// Ask for a file and open it
+    /// # Errors
+    /// Fails if the chosen file cannot be opened
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn open_dialog(&mut self) -> Result<(), AppError> {
+        match bladvak::rfd::FileDialog::new().pick_file() {
+            Some(path) => self.open_path(&path),
+            None => Ok(()),
+        }
+    }
+
+    /// Open the dropped files by path, before the framework reads them in memory
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn handle_dropped_paths(
+        &mut self,
+        ctx: &egui::Context,
+        error_manager: &mut ErrorManager,
+    ) {
+        let dropped = ctx.input_mut(|i| {
+            let (with_path, others) = std::mem::take(&mut i.raw.dropped_files)
+                .into_iter()
+                .partition::<Vec<_>, _>(|file| file.path.is_some());
+            i.raw.dropped_files = others;
+            with_path
+        });
+        for path in dropped.into_iter().filter_map(|file| file.path) {
+            if let Err(err) = self.open_path(&path) {
+                error_manager.add_error(err);
+            }
+        }
+    }
+}
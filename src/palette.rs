@@ -0,0 +1,258 @@
+//! Byte class colors
+
+use bladvak::eframe::egui::{self, Color32};
+
+/// Class of a byte
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ByteClass {
+    /// 0x00
+    Null,
+    /// 0xFF
+    Full,
+    /// printable ASCII
+    Printable,
+    /// ASCII whitespace
+    Whitespace,
+    /// ASCII control characters
+    Control,
+    /// bytes above 0x7F
+    High,
+}
+
+impl ByteClass {
+    /// Every class
+    pub(crate) const ALL: [ByteClass; 6] = [
+        ByteClass::Null,
+        ByteClass::Full,
+        ByteClass::Printable,
+        ByteClass::Whitespace,
+        ByteClass::Control,
+        ByteClass::High,
+    ];
+
+    /// Class of a byte
+    pub(crate) fn of(byte: u8) -> Self {
+        match byte {
+            0x00 => ByteClass::Null,
+            0xFF => ByteClass::Full,
+            b'\t' | b'\n' | 0x0B | 0x0C | b'\r' | b' ' => ByteClass::Whitespace,
+            0x21..=0x7E => ByteClass::Printable,
+            0x01..=0x1F | 0x7F => ByteClass::Control,
+            0x80..=0xFE => ByteClass::High,
+        }
+    }
+
+    /// Name of the class
+    fn name(self) -> &'static str {
+        match self {
+            ByteClass::Null => "0x00",
+            ByteClass::Full => "0xFF",
+            ByteClass::Printable => "Printable ASCII",
+            ByteClass::Whitespace => "Whitespace",
+            ByteClass::Control => "Control",
+            ByteClass::High => "High bytes",
+        }
+    }
+}
+
+/// Colors of each byte class (light theme, dark theme)
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub(crate) struct Palette {
+    /// palette name
+    pub(crate) name: String,
+    /// 0x00
+    null: (Color32, Color32),
+    /// 0xFF
+    full: (Color32, Color32),
+    /// printable ASCII
+    printable: (Color32, Color32),
+    /// ASCII whitespace
+    whitespace: (Color32, Color32),
+    /// ASCII control characters
+    control: (Color32, Color32),
+    /// bytes above 0x7F
+    high: (Color32, Color32),
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            name: "Default".to_string(),
+            null: (Color32::from_gray(170), Color32::from_gray(90)),
+            full: (
+                Color32::from_rgb(200, 110, 0),
+                Color32::from_rgb(255, 170, 60),
+            ),
+            printable: (
+                Color32::from_rgb(0, 120, 0),
+                Color32::from_rgb(120, 220, 120),
+            ),
+            whitespace: (
+                Color32::from_rgb(0, 120, 160),
+                Color32::from_rgb(100, 200, 230),
+            ),
+            control: (
+                Color32::from_rgb(180, 0, 120),
+                Color32::from_rgb(240, 120, 200),
+            ),
+            high: (
+                Color32::from_rgb(40, 60, 200),
+                Color32::from_rgb(140, 160, 255),
+            ),
+        }
+    }
+}
+
+impl Palette {
+    /// Colors of a class
+    fn colors_mut(&mut self, class: ByteClass) -> &mut (Color32, Color32) {
+        match class {
+            ByteClass::Null => &mut self.null,
+            ByteClass::Full => &mut self.full,
+            ByteClass::Printable => &mut self.printable,
+            ByteClass::Whitespace => &mut self.whitespace,
+            ByteClass::Control => &mut self.control,
+            ByteClass::High => &mut self.high,
+        }
+    }
+
+    /// Color of a byte
+    fn color(&self, byte: u8, is_light: bool) -> Color32 {
+        self.class_color(ByteClass::of(byte), is_light)
+    }
+
+    /// Color of a class
+    fn class_color(&self, class: ByteClass, is_light: bool) -> Color32 {
+        let (light, dark) = match class {
+            ByteClass::Null => self.null,
+            ByteClass::Full => self.full,
+            ByteClass::Printable => self.printable,
+            ByteClass::Whitespace => self.whitespace,
+            ByteClass::Control => self.control,
+            ByteClass::High => self.high,
+        };
+        if is_light { light } else { dark }
+    }
+}
+
+/// Byte class coloring settings
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub(crate) struct ByteColors {
+    /// color the bytes by class
+    pub(crate) enabled: bool,
+    /// available palettes
+    palettes: Vec<Palette>,
+    /// index of the palette in use
+    selected: usize,
+}
+
+impl Default for ByteColors {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            palettes: vec![Palette::default()],
+            selected: 0,
+        }
+    }
+}
+
+impl ByteColors {
+    /// Color of a byte, `None` when the coloring is disabled
+    pub(crate) fn color(&self, byte: u8, is_light: bool) -> Option<Color32> {
+        if !self.enabled {
+            return None;
+        }
+        self.palettes
+            .get(self.selected)
+            .map(|palette| palette.color(byte, is_light))
+    }
+
+    /// Color of a class in the selected palette, even when the coloring is disabled
+    pub(crate) fn class_color(&self, class: ByteClass, is_light: bool) -> Color32 {
+        self.palettes.get(self.selected).map_or_else(
+            || Palette::default().class_color(class, is_light),
+            |palette| palette.class_color(class, is_light),
+        )
+    }
+
+    /// Show the palette settings
+    pub(crate) fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.enabled, "Color bytes by class");
+        if !self.enabled {
+            return;
+        }
+        let selected_name = self
+            .palettes
+            .get(self.selected)
+            .map(|palette| palette.name.clone())
+            .unwrap_or_default();
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("byte_palette")
+                .selected_text(selected_name)
+                .show_ui(ui, |ui| {
+                    for (idx, palette) in self.palettes.iter().enumerate() {
+                        ui.selectable_value(&mut self.selected, idx, &palette.name);
+                    }
+                });
+            if ui.button("New").on_hover_text("Copy the palette").clicked() {
+                let mut palette = self
+                    .palettes
+                    .get(self.selected)
+                    .cloned()
+                    .unwrap_or_default();
+                palette.name = format!("Palette {}", self.palettes.len() + 1);
+                self.palettes.push(palette);
+                self.selected = self.palettes.len() - 1;
+            }
+            if ui
+                .add_enabled(self.palettes.len() > 1, egui::Button::new("Delete"))
+                .clicked()
+            {
+                self.palettes.remove(self.selected);
+                self.selected = self.selected.saturating_sub(1);
+            }
+        });
+        let Some(palette) = self.palettes.get_mut(self.selected) else {
+            return;
+        };
+        ui.collapsing("Edit palette", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Name");
+                ui.text_edit_singleline(&mut palette.name);
+            });
+            egui::Grid::new("byte_palette_colors").show(ui, |ui| {
+                ui.label("");
+                ui.label("Light");
+                ui.label("Dark");
+                ui.end_row();
+                for class in ByteClass::ALL {
+                    let (light, dark) = palette.colors_mut(class);
+                    ui.label(class.name());
+                    ui.color_edit_button_srgba(light);
+                    ui.color_edit_button_srgba(dark);
+                    ui.end_row();
+                }
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ByteClass;
+
+    #[test]
+    fn test_byte_classes() {
+        assert_eq!(ByteClass::of(0x00), ByteClass::Null);
+        assert_eq!(ByteClass::of(0xFF), ByteClass::Full);
+        assert_eq!(ByteClass::of(b'A'), ByteClass::Printable);
+        assert_eq!(ByteClass::of(b'~'), ByteClass::Printable);
+        assert_eq!(ByteClass::of(b' '), ByteClass::Whitespace);
+        assert_eq!(ByteClass::of(b'\n'), ByteClass::Whitespace);
+        assert_eq!(ByteClass::of(0x01), ByteClass::Control);
+        assert_eq!(ByteClass::of(0x7F), ByteClass::Control);
+        assert_eq!(ByteClass::of(0x80), ByteClass::High);
+        assert_eq!(ByteClass::of(0xFE), ByteClass::High);
+    }
+}
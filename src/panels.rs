@@ -7,6 +7,8 @@ use bladvak::errors::ErrorManager;
 use file_format::FileFormat;
 
 use crate::WombatApp;
+use crate::text_decoder::TextDecoder;
+use crate::words::WordFormat;
 
 /// File info
 #[derive(Debug, Default)]
@@ -39,6 +41,10 @@ impl BladvakPanel for FileInfo {
     fn ui(&self, app: &mut WombatApp, ui: &mut egui::Ui, _error_manager: &mut ErrorManager) {
         ui.label(format!("File: {}", app.filename.display()));
         ui.label(format!("{} bytes", app.binary_file.len()));
+        if app.binary_file.is_mapped() {
+            ui.label("Memory-mapped")
+                .on_hover_text("Pages are read from disk when displayed");
+        }
         #[allow(clippy::cast_precision_loss)]
         let binary_len = app.binary_file.len() as f64;
 
@@ -77,10 +83,14 @@ impl BladvakPanel for FileInfo {
 
         ui.separator();
         ui.label("Binary length");
-        ui.add(egui::Slider::new(
-            &mut app.display_settings.bytes_per_line,
-            1..=64,
-        ));
+        // whole groups per line, so the groups keep their byte order
+        let group_size = app.display_settings.group_size.max(1);
+        #[allow(clippy::cast_precision_loss)]
+        let step = group_size as f64;
+        ui.add(
+            egui::Slider::new(&mut app.display_settings.bytes_per_line, group_size..=64)
+                .step_by(step),
+        );
     }
 
     fn ui_settings(
@@ -99,5 +109,56 @@ impl BladvakPanel for FileInfo {
             &mut app.display_settings.display_lsb,
             "Show as Least Significant Bit",
         );
+        ui.checkbox(&mut app.display_settings.bit_view, "Bit view")
+            .on_hover_text("Show each byte as 8 bits, bits can be selected");
+        app.display_settings.offset_format.ui(ui);
+        egui::ComboBox::from_label("Group bytes")
+            .selected_text(app.display_settings.group_size.to_string())
+            .show_ui(ui, |ui| {
+                for group_size in [1, 2, 4, 8] {
+                    if ui
+                        .selectable_value(
+                            &mut app.display_settings.group_size,
+                            group_size,
+                            group_size.to_string(),
+                        )
+                        .changed()
+                    {
+                        app.display_settings.align_bytes_per_line();
+                    }
+                }
+            });
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut app.display_settings.little_endian, false, "Big endian");
+            ui.selectable_value(
+                &mut app.display_settings.little_endian,
+                true,
+                "Little endian",
+            );
+        });
+        egui::ComboBox::from_label("Show groups as")
+            .selected_text(app.display_settings.word_format.name())
+            .show_ui(ui, |ui| {
+                for format in WordFormat::ALL {
+                    ui.selectable_value(
+                        &mut app.display_settings.word_format,
+                        format,
+                        format.name(),
+                    );
+                }
+            });
+        egui::ComboBox::from_label("Text encoding")
+            .selected_text(app.display_settings.text_decoder.name())
+            .show_ui(ui, |ui| {
+                for decoder in TextDecoder::ALL {
+                    ui.selectable_value(
+                        &mut app.display_settings.text_decoder,
+                        decoder,
+                        decoder.name(),
+                    );
+                }
+            });
+        app.display_settings.byte_colors.ui(ui);
+        app.display_settings.minimap.ui(ui);
     }
 }
@@ -0,0 +1,87 @@
+//! Save files
+
+use bladvak::eframe::egui;
+use bladvak::errors::{AppError, ErrorManager};
+
+use crate::WombatApp;
+
+impl WombatApp {
+    /// Save the buffer to its file
+    /// # Errors
+    /// Fails if the file cannot be written
+    pub(crate) fn save(&mut self) -> Result<(), AppError> {
+        if bladvak::utils::is_native() && self.filename.is_file() {
+            self.binary_file.write_to(&self.filename)?;
+            self.history.mark_saved();
+            self.dirty = false;
+            self.rekey_bookmarks();
+            Ok(())
+        } else {
+            self.save_as()
+        }
+    }
+
+    /// Save the buffer to a file chosen by the user
+    /// # Errors
+    /// Fails if the file cannot be written
+    pub(crate) fn save_as(&mut self) -> Result<(), AppError> {
+        let Some(path) = bladvak::utils::get_save_path(Some(&self.filename))? else {
+            return Ok(());
+        };
+        if bladvak::utils::is_native() {
+            self.binary_file.write_to(&path)?;
+        } else {
+            // the browser downloads the file
+            bladvak::utils::save_file(&self.binary_file, &path)?;
+        }
+        self.filename = path;
+        self.history.mark_saved();
+        self.dirty = false;
+        self.rekey_bookmarks();
+        Ok(())
+    }
+
+    /// Ask what to do with unsaved changes before closing the active document
+    pub(crate) fn ui_unsaved_changes(
+        &mut self,
+        ui: &mut egui::Ui,
+        error_manager: &mut ErrorManager,
+    ) {
+        if !self.closing_document {
+            return;
+        }
+        let mut close = false;
+        let mut cancel = false;
+        egui::Modal::new(egui::Id::new("unsaved_changes")).show(ui.ctx(), |ui| {
+            ui.heading("Unsaved changes");
+            ui.label(format!(
+                "{} has been modified. Closing it will discard the changes.",
+                self.filename.display()
+            ));
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Save").clicked() {
+                    match self.save() {
+                        Ok(()) => close = true,
+                        Err(err) => {
+                            error_manager.add_error(err);
+                            cancel = true;
+                        }
+                    }
+                }
+                if ui.button("Discard").clicked() {
+                    close = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancel = true;
+                }
+            });
+        });
+        if close {
+            self.close_document();
+        }
+        if close || cancel {
+            self.closing_document = false;
+        }
+    }
+}
@@ -7,22 +7,84 @@ use bladvak::{
 };
 use std::path::PathBuf;
 
-use crate::{WombatApp, app::Accent};
+use crate::{
+    WombatApp,
+    app::Accent,
+    bits::{read_shown_bits, sign_extend},
+    history::Edit,
+    identifiers::{Identifier, mac_vendor},
+    literals::Literals,
+    offsets::OffsetFormat,
+    timestamps::Timestamp,
+};
 
 /// Selection
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
 pub(crate) struct Selection {
     /// Selection range
     pub(crate) range: Option<(usize, usize)>,
+    /// Other selected ranges, added with Ctrl+click
+    #[serde(default)]
+    pub(crate) others: Vec<(usize, usize)>,
+    /// Bit range refining the selection range in the bit view, bits numbered as displayed
+    #[serde(default)]
+    pub(crate) bits: Option<(usize, usize)>,
+    /// Bit fields are read least significant bit first
+    #[serde(default)]
+    pub(crate) lsb_first: bool,
     /// Selection color
     pub(crate) color: (Color32, Color32),
+    /// Edit cursor
+    #[serde(skip)]
+    pub(crate) cursor: Option<usize>,
+    /// Start of a selection extended from the keyboard
+    #[serde(skip)]
+    pub(crate) anchor: Option<usize>,
+    /// Offset to bring into view
+    #[serde(skip)]
+    pub(crate) scroll_to: Option<usize>,
+    /// Byte where the current mouse drag started
+    #[serde(skip)]
+    pub(crate) drag: Option<usize>,
+}
+
+/// Cursor movement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CursorMove {
+    /// previous byte
+    Left,
+    /// next byte
+    Right,
+    /// previous line
+    Up,
+    /// next line
+    Down,
+    /// previous page
+    PageUp,
+    /// next page
+    PageDown,
+    /// start of the line
+    LineStart,
+    /// end of the line
+    LineEnd,
+    /// start of the file
+    FileStart,
+    /// end of the file
+    FileEnd,
 }
 
 impl Default for Selection {
     fn default() -> Self {
         Self {
             range: None,
+            others: Vec::new(),
+            bits: None,
+            lsb_first: false,
             color: (Color32::ORANGE, Color32::GOLD),
+            cursor: None,
+            anchor: None,
+            scroll_to: None,
+            drag: None,
         }
     }
 }
@@ -31,6 +93,161 @@ impl Selection {
     /// reset selection
     pub(crate) fn reset(&mut self) {
         self.range = None;
+        self.others.clear();
+        self.bits = None;
+        self.cursor = None;
+        self.anchor = None;
+        self.drag = None;
+    }
+
+    /// clamp the selection to a file of `file_len` bytes
+    pub(crate) fn clamp(&mut self, file_len: usize) {
+        if file_len == 0 {
+            self.reset();
+        } else {
+            for (select1, select2) in self.range.iter_mut().chain(self.others.iter_mut()) {
+                *select1 = (*select1).min(file_len - 1);
+                *select2 = (*select2).min(file_len - 1);
+            }
+        }
+        if let Some(cursor) = self.cursor.as_mut() {
+            *cursor = (*cursor).min(file_len);
+        }
+        if let Some(anchor) = self.anchor.as_mut() {
+            *anchor = (*anchor).min(file_len - 1);
+        }
+    }
+
+    /// Every selected range, sorted and merged
+    pub(crate) fn ranges(&self) -> Vec<(usize, usize)> {
+        let mut ranges = self.others.clone();
+        ranges.extend(self.range);
+        ranges.sort_unstable();
+        let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        merged
+    }
+
+    /// `offset` is selected
+    pub(crate) fn contains(&self, offset: usize) -> bool {
+        self.range
+            .iter()
+            .chain(&self.others)
+            .any(|(start, end)| (*start..=*end).contains(&offset))
+    }
+
+    /// Keep the current range and select `start..=end` too
+    pub(crate) fn add_range(&mut self, start: usize, end: usize) {
+        if let Some(range) = self.range.take() {
+            self.others.push(range);
+        }
+        self.range = Some((start, end));
+        self.bits = None;
+    }
+
+    /// Unselect the range holding `offset`, returns `false` if `offset` is not selected
+    pub(crate) fn remove_range_at(&mut self, offset: usize) -> bool {
+        let holds = |(start, end): &(usize, usize)| (*start..=*end).contains(&offset);
+        if self.range.as_ref().is_some_and(holds) {
+            self.range = self.others.pop();
+            true
+        } else if let Some(idx) = self.others.iter().position(holds) {
+            self.others.remove(idx);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Selected bits, when they match the selection range
+    pub(crate) fn bit_range(&self) -> Option<(usize, usize)> {
+        self.bits
+            .filter(|(start, end)| self.range == Some((start / 8, end / 8)))
+    }
+
+    /// Select the bits `start..=end` and the bytes holding them
+    pub(crate) fn select_bits(&mut self, start: usize, end: usize) {
+        self.bits = Some((start, end));
+        self.range = Some((start / 8, end / 8));
+    }
+
+    /// Select a clicked bit, extending the bit selection when `extend` is set
+    pub(crate) fn click_bit(&mut self, bit: usize, extend: bool) {
+        match self.bit_range() {
+            Some((start, end)) if extend => self.select_bits(start.min(bit), end.max(bit)),
+            _ => self.select_bits(bit, bit),
+        }
+    }
+
+    /// Current position: the cursor or the start of the selection
+    pub(crate) fn position(&self) -> usize {
+        self.cursor
+            .or(self.range.map(|(start, _)| start))
+            .unwrap_or(0)
+    }
+
+    /// Put the cursor on `offset`, select it and bring it into view
+    pub(crate) fn select_offset(&mut self, offset: usize) {
+        self.select_range(offset, offset);
+    }
+
+    /// Select `start..=end`, put the cursor on `start` and bring it into view
+    pub(crate) fn select_range(&mut self, start: usize, end: usize) {
+        self.cursor = Some(start);
+        self.anchor = None;
+        self.range = Some((start, end));
+        self.others.clear();
+        self.scroll_to = Some(start);
+    }
+
+    /// Move the cursor, extending the selection from the anchor when `extend` is set
+    pub(crate) fn move_cursor(
+        &mut self,
+        movement: CursorMove,
+        extend: bool,
+        (bytes_per_line, page_lines): (usize, usize),
+        file_len: usize,
+    ) {
+        let Some(last) = file_len.checked_sub(1) else {
+            return;
+        };
+        let current = self.position().min(last);
+        let page = bytes_per_line * page_lines.max(1);
+        let line_start = current - current % bytes_per_line;
+        let next = match movement {
+            CursorMove::Left => current.saturating_sub(1),
+            CursorMove::Right => current + 1,
+            CursorMove::Up => current.checked_sub(bytes_per_line).unwrap_or(current),
+            CursorMove::Down => {
+                if current + bytes_per_line <= last {
+                    current + bytes_per_line
+                } else {
+                    current
+                }
+            }
+            CursorMove::PageUp => current.saturating_sub(page),
+            CursorMove::PageDown => current + page,
+            CursorMove::LineStart => line_start,
+            CursorMove::LineEnd => line_start + bytes_per_line - 1,
+            CursorMove::FileStart => 0,
+            CursorMove::FileEnd => last,
+        }
+        .min(last);
+        if extend {
+            let anchor = *self.anchor.get_or_insert(current);
+            self.range = Some((anchor.min(next), anchor.max(next)));
+        } else {
+            self.anchor = None;
+            self.range = Some((next, next));
+            self.others.clear();
+        }
+        self.cursor = Some(next);
+        self.scroll_to = Some(next);
     }
 }
 
@@ -38,6 +255,170 @@ impl Selection {
 #[derive(Debug)]
 pub(crate) struct PanelSelection;
 
+impl PanelSelection {
+    /// Show the selection bounds, the fields show addresses but hold offsets
+    fn ui_bounds(
+        ui: &mut egui::Ui,
+        (select1, select2): (&mut usize, &mut usize),
+        file_len: usize,
+        offset_format: &OffsetFormat,
+    ) {
+        let offset_digits = offset_format.digits(file_len);
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        let formatter = |value: f64, _| offset_format.format(value as usize, offset_digits);
+        #[allow(clippy::cast_precision_loss)]
+        let parser = |text: &str| offset_format.parse(text).map(|offset| offset as f64);
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(select1)
+                    .range(0..=*select2)
+                    .custom_formatter(formatter)
+                    .custom_parser(parser),
+            );
+            ui.label("->");
+            ui.add(
+                egui::DragValue::new(select2)
+                    .range(*select1..=file_len.saturating_sub(1))
+                    .custom_formatter(formatter)
+                    .custom_parser(parser),
+            );
+        });
+    }
+}
+
+impl PanelSelection {
+    /// Show the actions on every selected range, returns the deletion of the ranges
+    fn ui_actions(
+        ui: &mut egui::Ui,
+        data: &[u8],
+        ranges: &[(usize, usize)],
+        literals: &mut Literals,
+        error_manager: &mut ErrorManager,
+    ) -> Option<Edit> {
+        let mut edit = None;
+        ui.collapsing("More", |ui| {
+            if ui.button("Delete selection").clicked() {
+                edit = Some(Edit::delete_ranges(data, ranges));
+            }
+            let slices = ranges
+                .iter()
+                .filter_map(|(start, end)| data.get(*start..=*end))
+                .collect::<Vec<_>>();
+            let as_hex = || {
+                slices
+                    .iter()
+                    .map(|slice| {
+                        slice
+                            .iter()
+                            .map(|byte| format!("{byte:02X}"))
+                            .collect::<Vec<String>>()
+                            .join(" ")
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            };
+            if ui.button("Export as raw").clicked()
+                && let Err(e) =
+                    bladvak::utils::save_file(&slices.concat(), &PathBuf::from("exported.bin"))
+            {
+                error_manager.add_error(e);
+            }
+            if ui.button("Export as hex").clicked()
+                && let Err(e) =
+                    bladvak::utils::save_file(as_hex().as_bytes(), &PathBuf::from("exported.hex"))
+            {
+                error_manager.add_error(e);
+            }
+            if ui.button("Copy as hex").clicked() {
+                ui.ctx().copy_text(as_hex());
+            }
+            ui.separator();
+            literals.ui(ui);
+            ui.horizontal(|ui| {
+                let format_name = literals.format_name();
+                if ui.button(format!("Copy as {format_name}")).clicked() {
+                    ui.ctx().copy_text(literals.format(&slices.concat()));
+                }
+                if ui.button("Export").clicked()
+                    && let Err(e) = bladvak::utils::save_file(
+                        literals.format(&slices.concat()).as_bytes(),
+                        &PathBuf::from(literals.file_name()),
+                    )
+                {
+                    error_manager.add_error(e);
+                }
+            });
+        });
+        edit
+    }
+
+    /// Show the GUIDs, addresses and ports of the size of the selection
+    fn ui_identifiers(ui: &mut egui::Ui, bytes: &[u8]) {
+        for identifier in Identifier::ALL {
+            let Some(text) = identifier.decode(bytes) else {
+                continue;
+            };
+            if identifier == Identifier::Mac {
+                let vendor = mac_vendor(bytes).unwrap_or("unknown vendor");
+                ui.label(format!("{} {text} ({vendor})", identifier.name()));
+            } else {
+                ui.label(format!("{} {text}", identifier.name()));
+            }
+        }
+    }
+
+    /// Decode a bit field of the selection, bit offsets are the ones of the bit view
+    fn ui_bit_field(ui: &mut egui::Ui, selection: &mut Selection, data: &[u8], display_lsb: bool) {
+        let Some((select1, select2)) = selection.range else {
+            return;
+        };
+        if data.is_empty() {
+            // a selection may outlive the bytes, e.g. after an undo
+            return;
+        }
+        let last_bit = (data.len() * 8).saturating_sub(1);
+        let (mut bit_offset, bit_end) = selection
+            .bit_range()
+            .unwrap_or((select1 * 8, select2 * 8 + 7));
+        let selected_bits = bit_end - bit_offset + 1;
+        let mut bit_len = selected_bits.min(64);
+        ui.separator();
+        ui.label("Bit field");
+        let changed = ui
+            .horizontal(|ui| {
+                ui.label("offset");
+                let offset = ui.add(egui::DragValue::new(&mut bit_offset).range(0..=last_bit));
+                ui.label("length");
+                let len = ui.add(egui::DragValue::new(&mut bit_len).range(1..=64));
+                offset.changed() || len.changed()
+            })
+            .inner;
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut selection.lsb_first, false, "MSB first");
+            ui.selectable_value(&mut selection.lsb_first, true, "LSB first");
+        });
+        let bit_end = (bit_offset + bit_len).min(data.len() * 8).saturating_sub(1);
+        if changed {
+            selection.select_bits(bit_offset, bit_end);
+        }
+        if !changed && selected_bits > 64 {
+            ui.label(format!(
+                "{selected_bits} bits selected, decoding the first 64"
+            ));
+        }
+        if (bit_end + 1).saturating_sub(bit_offset) < bit_len {
+            ui.label("Field past the end of the file");
+        } else if let Some(value) =
+            read_shown_bits(data, bit_offset, bit_len, selection.lsb_first, display_lsb)
+        {
+            ui.label(format!("unsigned {value}"));
+            ui.label(format!("signed {}", sign_extend(value, bit_len)));
+            ui.label(format!("hex 0x{value:X}"));
+        }
+    }
+}
+
 impl BladvakPanel for PanelSelection {
     type App = WombatApp;
     fn name(&self) -> &'static str {
@@ -51,7 +432,7 @@ impl BladvakPanel for PanelSelection {
     }
     fn ui(&self, app: &mut WombatApp, ui: &mut egui::Ui, error_manager: &mut ErrorManager) {
         if let Some((select1, select2)) = app.selection.range.as_mut() {
-            let mut mark_stale = false;
+            let mut edit = None;
             ui.horizontal(|ui| {
                 ui.label("Selection");
                 let color_to_edit = if ui.ctx().theme() == Theme::Light {
@@ -61,25 +442,37 @@ impl BladvakPanel for PanelSelection {
                 };
                 ui.color_edit_button_srgba(color_to_edit);
             });
-            ui.horizontal(|ui| {
-                ui.add(egui::DragValue::new(select1).range(0..=*select2));
-                let max = app.binary_file.len() - 1;
-                ui.label("->");
-                ui.add(egui::DragValue::new(select2).range(*select1..=max));
-            });
+            let offset_format = &app.display_settings.offset_format;
+            let offset_digits = offset_format.digits(app.binary_file.len());
+            Self::ui_bounds(ui, (select1, select2), app.binary_file.len(), offset_format);
             if select1 == select2
+                && app.selection.others.is_empty()
                 && let Some(current) = app.binary_file.get(*select1)
             {
                 ui.separator();
-                ui.label(format!("byte at index {select1}"));
-                WombatApp::ui_table_u8(ui, *current, &Accent::Hex);
+                ui.label(format!(
+                    "byte at index {select1}, address {}",
+                    offset_format.format(*select1, offset_digits)
+                ));
+                let decoder = app.display_settings.text_decoder;
+                let glyph = decoder.decode_at(&app.binary_file, *select1);
+                WombatApp::ui_table_u8(ui, *current, &Accent::Hex, Some((decoder, glyph)));
             } else {
-                let nb_selected = select2.checked_sub(*select1).map_or(0, |d| d as u64 + 1);
-                ui.label(format!("{nb_selected} bytes selected"));
                 let range = *select1..=*select2;
+                let ranges = app.selection.ranges();
+                let nb_selected: usize = ranges.iter().map(|(start, end)| end - start + 1).sum();
+                if ranges.len() > 1 {
+                    ui.label(format!(
+                        "{nb_selected} bytes selected in {} ranges",
+                        ranges.len()
+                    ));
+                } else {
+                    ui.label(format!("{nb_selected} bytes selected"));
+                }
 
                 if nb_selected == 4
-                    && let Some(slice) = app.binary_file.get(range.clone())
+                    && ranges.len() == 1
+                    && let Some(slice) = app.binary_file.get(range)
                     && let Ok(bytes) = <[u8; 4]>::try_from(slice)
                 {
                     let range_u32 = u32::from_le_bytes(bytes);
@@ -94,45 +487,42 @@ impl BladvakPanel for PanelSelection {
                     }
                 }
 
-                ui.collapsing("More", |ui| {
-                    if ui.button("Delete selection").clicked() {
-                        app.binary_file.drain(range.clone());
-                        *select2 = select1.checked_sub(1).unwrap_or(0);
-                        mark_stale = true;
+                if ranges.len() == 1
+                    && let Some(bytes) = app.binary_file.get(ranges[0].0..=ranges[0].1)
+                {
+                    Self::ui_identifiers(ui, bytes);
+                    if nb_selected >= 4 {
+                        ui.collapsing("Timestamps", |ui| Timestamp::ui_table(ui, bytes));
                     }
-                    if let Some(slice) = app.binary_file.get(range) {
-                        if ui.button("Export as raw").clicked()
-                            && let Err(e) =
-                                bladvak::utils::save_file(slice, &PathBuf::from("exported.bin"))
-                        {
-                            error_manager.add_error(e);
-                        }
-                        if ui.button("Export as hex").clicked() {
-                            let file_as_hex = slice
-                                .iter()
-                                .map(|byte| format!("{byte:02X}"))
-                                .collect::<Vec<String>>()
-                                .join(" ");
-                            if let Err(e) = bladvak::utils::save_file(
-                                file_as_hex.as_bytes(),
-                                &PathBuf::from("exported.hex"),
-                            ) {
-                                error_manager.add_error(e);
-                            }
-                        }
-                        if ui.button("Copy as hex").clicked() {
-                            let file_as_hex = slice
-                                .iter()
-                                .map(|byte| format!("{byte:02X}"))
-                                .collect::<Vec<String>>()
-                                .join(" ");
-                            ui.ctx().copy_text(file_as_hex);
+                }
+
+                edit = Self::ui_actions(
+                    ui,
+                    &app.binary_file,
+                    &ranges,
+                    &mut app.literals,
+                    error_manager,
+                );
+            }
+            Self::ui_bit_field(
+                ui,
+                &mut app.selection,
+                &app.binary_file,
+                app.display_settings.display_lsb,
+            );
+            if let Some(edit) = edit {
+                let start = edit.range().map(|range| *range.start());
+                match app.apply_edit(edit) {
+                    Err(err) => error_manager.add_error(err),
+                    Ok(false) => {}
+                    Ok(true) => {
+                        if let Some(start) = start {
+                            app.selection.range = Some((start, start));
+                            app.selection.others.clear();
                         }
+                        app.selection.clamp(app.binary_file.len());
                     }
-                });
-            }
-            if mark_stale {
-                app.stale();
+                }
             }
         } else {
             ui.label("No selection");
@@ -147,3 +537,61 @@ impl BladvakPanel for PanelSelection {
     ) {
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{CursorMove, Selection};
+
+    #[test]
+    fn test_move_cursor() {
+        let mut selection = Selection::default();
+        let layout = (16, 4);
+        selection.move_cursor(CursorMove::Down, false, layout, 100);
+        assert_eq!(selection.cursor, Some(16));
+        selection.move_cursor(CursorMove::LineEnd, false, layout, 100);
+        assert_eq!(selection.cursor, Some(31));
+        selection.move_cursor(CursorMove::PageDown, false, layout, 100);
+        assert_eq!(selection.cursor, Some(95));
+        selection.move_cursor(CursorMove::Down, false, layout, 100);
+        assert_eq!(selection.cursor, Some(95));
+        selection.move_cursor(CursorMove::FileEnd, false, layout, 100);
+        assert_eq!(selection.cursor, Some(99));
+        selection.move_cursor(CursorMove::Right, false, layout, 100);
+        assert_eq!(selection.cursor, Some(99));
+        selection.move_cursor(CursorMove::FileStart, false, layout, 100);
+        assert_eq!(selection.cursor, Some(0));
+        selection.move_cursor(CursorMove::Up, false, layout, 100);
+        assert_eq!(selection.cursor, Some(0));
+    }
+
+    #[test]
+    fn test_extend_selection() {
+        let mut selection = Selection::default();
+        let layout = (16, 4);
+        selection.cursor = Some(20);
+        selection.move_cursor(CursorMove::Right, true, layout, 100);
+        selection.move_cursor(CursorMove::Right, true, layout, 100);
+        assert_eq!(selection.range, Some((20, 22)));
+        selection.move_cursor(CursorMove::Up, true, layout, 100);
+        assert_eq!(selection.range, Some((6, 20)));
+        selection.move_cursor(CursorMove::Left, false, layout, 100);
+        assert_eq!(selection.range, Some((5, 5)));
+        assert_eq!(selection.anchor, None);
+    }
+
+    #[test]
+    fn test_multiple_ranges() {
+        let mut selection = Selection::default();
+        selection.select_range(10, 12);
+        selection.add_range(2, 4);
+        selection.add_range(13, 15);
+        assert_eq!(selection.ranges(), [(2, 4), (10, 15)]);
+        assert!(selection.contains(3) && !selection.contains(5));
+        assert!(selection.remove_range_at(14));
+        assert_eq!(selection.range, Some((2, 4)));
+        assert!(!selection.remove_range_at(14));
+        assert_eq!(selection.ranges(), [(2, 4), (10, 12)]);
+        selection.clamp(11);
+        assert_eq!(selection.ranges(), [(2, 4), (10, 10)]);
+    }
+}
@@ -0,0 +1,109 @@
+//! Two hex views of the same buffer
+
+use bladvak::eframe::egui;
+
+/// Split of the central panel
+#[derive(serde::Deserialize, serde::Serialize, Debug, Default)]
+#[serde(default)]
+pub(crate) struct SplitView {
+    /// show two views
+    pub(crate) enabled: bool,
+    /// keep the views a fixed distance apart
+    locked: bool,
+    /// scroll offset of the second view minus the one of the first view
+    #[serde(skip)]
+    distance: f32,
+    /// scroll offsets of the views, from the last frame
+    #[serde(skip)]
+    offsets: [f32; 2],
+    /// scroll offsets to apply to the views
+    #[serde(skip)]
+    pending: [Option<f32>; 2],
+    /// the views whose scroll offset was applied this frame
+    #[serde(skip)]
+    forced: [bool; 2],
+    /// view used by the keyboard, the last one clicked
+    #[serde(skip)]
+    pub(crate) active: usize,
+}
+
+impl SplitView {
+    /// Smallest scroll that moves the other view, in points
+    const MIN_SCROLL: f32 = 0.5;
+
+    /// Scroll offset to apply to `view`
+    pub(crate) fn take_pending(&mut self, view: usize) -> Option<f32> {
+        let pending = self.pending[view].take();
+        self.forced[view] = pending.is_some();
+        pending
+    }
+
+    /// Keep the distance between the views once both are shown with `offsets`,
+    /// returns `true` when a view has to be scrolled
+    pub(crate) fn sync(&mut self, offsets: [f32; 2]) -> bool {
+        let previous = std::mem::replace(&mut self.offsets, offsets);
+        let forced = std::mem::take(&mut self.forced);
+        if !self.locked {
+            return false;
+        }
+        // the view scrolled by the user drives the other one
+        let moved = |view: usize| {
+            !forced[view] && (offsets[view] - previous[view]).abs() >= Self::MIN_SCROLL
+        };
+        let (view, target) = if moved(0) {
+            (1, offsets[0] + self.distance)
+        } else if moved(1) {
+            (0, offsets[1] - self.distance)
+        } else {
+            return false;
+        };
+        let target = target.max(0.0);
+        if (offsets[view] - target).abs() < Self::MIN_SCROLL {
+            return false;
+        }
+        self.pending[view] = Some(target);
+        true
+    }
+
+    /// Show the lock of the views
+    pub(crate) fn ui(&mut self, ui: &mut egui::Ui, row_height: f32) {
+        ui.horizontal(|ui| {
+            if ui
+                .toggle_value(&mut self.locked, "Lock scroll")
+                .on_hover_text("Keep the views the same distance apart")
+                .changed()
+            {
+                self.distance = self.offsets[1] - self.offsets[0];
+            }
+            if self.locked {
+                #[allow(clippy::cast_possible_truncation)]
+                let lines = (self.distance / row_height).round() as i64;
+                ui.label(format!("{lines} lines apart"));
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SplitView;
+
+    #[test]
+    fn test_locked_views_follow() {
+        let mut split = SplitView {
+            locked: true,
+            distance: 100.0,
+            offsets: [0.0, 100.0],
+            ..SplitView::default()
+        };
+        assert!(split.sync([20.0, 100.0]));
+        assert_eq!(split.take_pending(0), None);
+        assert_eq!(split.take_pending(1), Some(120.0));
+        // the forced view does not drive the first one back
+        assert!(!split.sync([20.0, 110.0]));
+        assert!(split.sync([20.0, 150.0]));
+        assert_eq!(split.take_pending(0), Some(50.0));
+        split.locked = false;
+        assert!(!split.sync([0.0, 0.0]));
+    }
+}
@@ -0,0 +1,345 @@
+//! Character encodings of the text column
+
+use encoding_rs::Encoding;
+
+/// CP437 characters of the bytes 0x80 to 0xFF
+const CP437_HIGH: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', //
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', //
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', //
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐', //
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧', //
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀', //
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', //
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{A0}',
+];
+
+/// Latin-1 code point of every EBCDIC (CP037) byte
+const CP037: [u8; 256] = [
+    0x00, 0x01, 0x02, 0x03, 0x9C, 0x09, 0x86, 0x7F, 0x97, 0x8D, 0x8E, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F,
+    0x10, 0x11, 0x12, 0x13, 0x9D, 0x85, 0x08, 0x87, 0x18, 0x19, 0x92, 0x8F, 0x1C, 0x1D, 0x1E, 0x1F,
+    0x80, 0x81, 0x82, 0x83, 0x84, 0x0A, 0x17, 0x1B, 0x88, 0x89, 0x8A, 0x8B, 0x8C, 0x05, 0x06, 0x07,
+    0x90, 0x91, 0x16, 0x93, 0x94, 0x95, 0x96, 0x04, 0x98, 0x99, 0x9A, 0x9B, 0x14, 0x15, 0x9E, 0x1A,
+    0x20, 0xA0, 0xE2, 0xE4, 0xE0, 0xE1, 0xE3, 0xE5, 0xE7, 0xF1, 0xA2, 0x2E, 0x3C, 0x28, 0x2B, 0x7C,
+    0x26, 0xE9, 0xEA, 0xEB, 0xE8, 0xED, 0xEE, 0xEF, 0xEC, 0xDF, 0x21, 0x24, 0x2A, 0x29, 0x3B, 0xAC,
+    0x2D, 0x2F, 0xC2, 0xC4, 0xC0, 0xC1, 0xC3, 0xC5, 0xC7, 0xD1, 0xA6, 0x2C, 0x25, 0x5F, 0x3E, 0x3F,
+    0xF8, 0xC9, 0xCA, 0xCB, 0xC8, 0xCD, 0xCE, 0xCF, 0xCC, 0x60, 0x3A, 0x23, 0x40, 0x27, 0x3D, 0x22,
+    0xD8, 0x61, 0x62, 0x63, 0x64, 0x65, 0x66, 0x67, 0x68, 0x69, 0xAB, 0xBB, 0xF0, 0xFD, 0xFE, 0xB1,
+    0xB0, 0x6A, 0x6B, 0x6C, 0x6D, 0x6E, 0x6F, 0x70, 0x71, 0x72, 0xAA, 0xBA, 0xE6, 0xB8, 0xC6, 0xA4,
+    0xB5, 0x7E, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79, 0x7A, 0xA1, 0xBF, 0xD0, 0xDD, 0xDE, 0xAE,
+    0x5E, 0xA3, 0xA5, 0xB7, 0xA9, 0xA7, 0xB6, 0xBC, 0xBD, 0xBE, 0x5B, 0x5D, 0xAF, 0xA8, 0xB4, 0xD7,
+    0x7B, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0xAD, 0xF4, 0xF6, 0xF2, 0xF3, 0xF5,
+    0x7D, 0x4A, 0x4B, 0x4C, 0x4D, 0x4E, 0x4F, 0x50, 0x51, 0x52, 0xB9, 0xFB, 0xFC, 0xF9, 0xFA, 0xFF,
+    0x5C, 0xF7, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5A, 0xB2, 0xD4, 0xD6, 0xD2, 0xD3, 0xD5,
+    0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0xB3, 0xDB, 0xDC, 0xD9, 0xDA, 0x9F,
+];
+
+/// Decoder of the text column
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) enum TextDecoder {
+    /// ASCII
+    Ascii,
+    /// ISO-8859-1
+    Latin1,
+    /// Windows-1252
+    Windows1252,
+    /// IBM PC code page 437
+    Cp437,
+    /// EBCDIC code page 037
+    Ebcdic,
+    /// UTF-8
+    Utf8,
+    /// UTF-16 little endian
+    Utf16Le,
+    /// UTF-16 big endian
+    Utf16Be,
+    /// Shift-JIS
+    ShiftJis,
+}
+
+/// Decoded cell of the text column
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Glyph {
+    /// character starting at the byte
+    Char(char),
+    /// byte inside a character started by a previous byte
+    Continuation,
+    /// byte that cannot be decoded
+    Invalid,
+}
+
+impl Glyph {
+    /// Character drawn in the text column
+    pub(crate) fn display(self) -> char {
+        match self {
+            Glyph::Char(c) if !c.is_control() && !c.is_whitespace() => c,
+            Glyph::Continuation => ' ',
+            Glyph::Char(_) | Glyph::Invalid => '.',
+        }
+    }
+
+    /// Description of the decoded code point
+    pub(crate) fn describe(self) -> String {
+        match self {
+            Glyph::Char(c) if c.is_control() => format!("U+{:04X}", u32::from(c)),
+            Glyph::Char(c) => format!("U+{:04X} {c}", u32::from(c)),
+            Glyph::Continuation => "continuation byte".to_string(),
+            Glyph::Invalid => "invalid".to_string(),
+        }
+    }
+}
+
+impl TextDecoder {
+    /// Every decoder
+    pub(crate) const ALL: [TextDecoder; 9] = [
+        TextDecoder::Ascii,
+        TextDecoder::Latin1,
+        TextDecoder::Windows1252,
+        TextDecoder::Cp437,
+        TextDecoder::Ebcdic,
+        TextDecoder::Utf8,
+        TextDecoder::Utf16Le,
+        TextDecoder::Utf16Be,
+        TextDecoder::ShiftJis,
+    ];
+
+    /// Name of the decoder
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            TextDecoder::Ascii => "ASCII",
+            TextDecoder::Latin1 => "Latin-1",
+            TextDecoder::Windows1252 => "Windows-1252",
+            TextDecoder::Cp437 => "CP437",
+            TextDecoder::Ebcdic => "EBCDIC (CP037)",
+            TextDecoder::Utf8 => "UTF-8",
+            TextDecoder::Utf16Le => "UTF-16LE",
+            TextDecoder::Utf16Be => "UTF-16BE",
+            TextDecoder::ShiftJis => "Shift-JIS",
+        }
+    }
+
+    /// Number of bytes before a cell needed to decode it
+    pub(crate) fn context(self) -> usize {
+        match self {
+            TextDecoder::Ascii
+            | TextDecoder::Latin1
+            | TextDecoder::Windows1252
+            | TextDecoder::Cp437
+            | TextDecoder::Ebcdic => 0,
+            TextDecoder::Utf8 | TextDecoder::Utf16Le | TextDecoder::Utf16Be => 4,
+            TextDecoder::ShiftJis => 16,
+        }
+    }
+
+    /// Decode the cell of the byte at `offset`
+    ///
+    /// UTF-16 units are aligned on the start of `data`
+    pub(crate) fn decode_at(self, data: &[u8], offset: usize) -> Glyph {
+        let Some(&byte) = data.get(offset) else {
+            return Glyph::Invalid;
+        };
+        match self {
+            TextDecoder::Ascii if byte.is_ascii() => Glyph::Char(char::from(byte)),
+            TextDecoder::Ascii => Glyph::Invalid,
+            TextDecoder::Latin1 => Glyph::Char(char::from(byte)),
+            TextDecoder::Windows1252 => decode_with(encoding_rs::WINDOWS_1252, &[byte]),
+            TextDecoder::Cp437 => match byte.checked_sub(0x80) {
+                Some(high) => Glyph::Char(CP437_HIGH[usize::from(high)]),
+                None => Glyph::Char(char::from(byte)),
+            },
+            TextDecoder::Ebcdic => Glyph::Char(char::from(CP037[usize::from(byte)])),
+            TextDecoder::Utf8 => utf8_at(data, offset),
+            TextDecoder::Utf16Le => utf16_at(data, offset, u16::from_le_bytes),
+            TextDecoder::Utf16Be => utf16_at(data, offset, u16::from_be_bytes),
+            TextDecoder::ShiftJis => shift_jis_at(data, offset),
+        }
+    }
+}
+
+/// Decode a single character with an `encoding_rs` encoding
+fn decode_with(encoding: &'static Encoding, bytes: &[u8]) -> Glyph {
+    let decoded = encoding.decode_without_bom_handling_and_without_replacement(bytes);
+    let mut chars = decoded.iter().flat_map(|text| text.chars());
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Glyph::Char(c),
+        _ => Glyph::Invalid,
+    }
+}
+
+/// Length of the UTF-8 sequence started by `byte`, 0 for continuation and invalid bytes
+fn utf8_len(byte: u8) -> usize {
+    match byte {
+        0x00..=0x7F => 1,
+        0xC2..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF4 => 4,
+        _ => 0,
+    }
+}
+
+/// Decode a UTF-8 cell
+fn utf8_at(data: &[u8], offset: usize) -> Glyph {
+    let is_valid = |start: usize, len: usize| {
+        data.get(start..start + len)
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+            .and_then(|text| text.chars().next())
+    };
+    let len = utf8_len(data[offset]);
+    if len > 0 {
+        return is_valid(offset, len).map_or(Glyph::Invalid, Glyph::Char);
+    }
+    for back in 1..=3 {
+        let Some(start) = offset.checked_sub(back) else {
+            break;
+        };
+        let len = utf8_len(data[start]);
+        if len > 0 {
+            if len > back && is_valid(start, len).is_some() {
+                return Glyph::Continuation;
+            }
+            break;
+        }
+    }
+    Glyph::Invalid
+}
+
+/// Decode a UTF-16 cell
+fn utf16_at(data: &[u8], offset: usize, from_bytes: fn([u8; 2]) -> u16) -> Glyph {
+    let unit = |start: usize| {
+        data.get(start..start + 2)
+            .map(|bytes| from_bytes([bytes[0], bytes[1]]))
+    };
+    let start = offset - offset % 2;
+    let glyph = match unit(start) {
+        None => Glyph::Invalid,
+        Some(high @ 0xD800..=0xDBFF) => match unit(start + 2) {
+            Some(low @ 0xDC00..=0xDFFF) => char::decode_utf16([high, low])
+                .next()
+                .and_then(Result::ok)
+                .map_or(Glyph::Invalid, Glyph::Char),
+            _ => Glyph::Invalid,
+        },
+        Some(0xDC00..=0xDFFF) => match start.checked_sub(2).and_then(unit) {
+            Some(0xD800..=0xDBFF) => Glyph::Continuation,
+            _ => Glyph::Invalid,
+        },
+        Some(code) => char::from_u32(u32::from(code)).map_or(Glyph::Invalid, Glyph::Char),
+    };
+    if offset == start || glyph == Glyph::Invalid {
+        glyph
+    } else {
+        Glyph::Continuation
+    }
+}
+
+/// Is `byte` the first byte of a Shift-JIS double byte character
+fn is_shift_jis_lead(byte: u8) -> bool {
+    matches!(byte, 0x81..=0x9F | 0xE0..=0xFC)
+}
+
+/// Decode a Shift-JIS cell
+fn shift_jis_at(data: &[u8], offset: usize) -> Glyph {
+    let byte = data[offset];
+    // lead bytes before the cell pair up from the first one
+    let leads = data[..offset]
+        .iter()
+        .rev()
+        .take_while(|b| is_shift_jis_lead(**b))
+        .count();
+    if leads % 2 == 1
+        && let Glyph::Char(_) = decode_with(encoding_rs::SHIFT_JIS, &data[offset - 1..=offset])
+    {
+        return Glyph::Continuation;
+    }
+    if is_shift_jis_lead(byte) {
+        data.get(offset..offset + 2).map_or(Glyph::Invalid, |pair| {
+            decode_with(encoding_rs::SHIFT_JIS, pair)
+        })
+    } else {
+        decode_with(encoding_rs::SHIFT_JIS, &[byte])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Glyph, TextDecoder};
+
+    /// Decode every cell of `data`
+    fn decode(decoder: TextDecoder, data: &[u8]) -> Vec<Glyph> {
+        (0..data.len())
+            .map(|offset| decoder.decode_at(data, offset))
+            .collect()
+    }
+
+    #[test]
+    fn test_single_byte_decoders() {
+        assert_eq!(TextDecoder::Latin1.decode_at(&[0xE9], 0), Glyph::Char('é'));
+        assert_eq!(
+            TextDecoder::Windows1252.decode_at(&[0x80], 0),
+            Glyph::Char('€')
+        );
+        assert_eq!(TextDecoder::Cp437.decode_at(&[0xB0], 0), Glyph::Char('░'));
+        assert_eq!(TextDecoder::Ebcdic.decode_at(&[0xC1], 0), Glyph::Char('A'));
+        assert_eq!(TextDecoder::Ebcdic.decode_at(&[0xF0], 0), Glyph::Char('0'));
+        assert_eq!(TextDecoder::Ascii.decode_at(&[0x80], 0), Glyph::Invalid);
+    }
+
+    #[test]
+    fn test_utf8_spans_cells() {
+        let data = "aé€".as_bytes();
+        assert_eq!(
+            decode(TextDecoder::Utf8, data),
+            vec![
+                Glyph::Char('a'),
+                Glyph::Char('é'),
+                Glyph::Continuation,
+                Glyph::Char('€'),
+                Glyph::Continuation,
+                Glyph::Continuation,
+            ]
+        );
+        assert_eq!(
+            decode(TextDecoder::Utf8, &[0x80, 0xC3]),
+            vec![Glyph::Invalid, Glyph::Invalid]
+        );
+    }
+
+    #[test]
+    fn test_utf16() {
+        let data = [0x41, 0x00, 0x3D, 0xD8, 0x00, 0xDE];
+        assert_eq!(
+            decode(TextDecoder::Utf16Le, &data),
+            vec![
+                Glyph::Char('A'),
+                Glyph::Continuation,
+                Glyph::Char('😀'),
+                Glyph::Continuation,
+                Glyph::Continuation,
+                Glyph::Continuation,
+            ]
+        );
+        assert_eq!(
+            TextDecoder::Utf16Be.decode_at(&[0x00, 0x41], 0),
+            Glyph::Char('A')
+        );
+        assert_eq!(
+            TextDecoder::Utf16Le.decode_at(&[0x00, 0xDE], 0),
+            Glyph::Invalid
+        );
+    }
+
+    #[test]
+    fn test_shift_jis() {
+        // "aあ" then a half-width katakana
+        let data = [0x61, 0x82, 0xA0, 0xB1];
+        assert_eq!(
+            decode(TextDecoder::ShiftJis, &data),
+            vec![
+                Glyph::Char('a'),
+                Glyph::Char('あ'),
+                Glyph::Continuation,
+                Glyph::Char('ｱ'),
+            ]
+        );
+    }
+}
@@ -0,0 +1,345 @@
+//! Timestamp decoding
+
+use bladvak::eframe::egui::{self, Sense};
+
+/// Seconds in a day
+const DAY: i64 = 86_400;
+
+/// Months starting with a leap second added to UTC since the GPS epoch
+const LEAP_SECONDS: [(i64, i64); 18] = [
+    (1981, 7),
+    (1982, 7),
+    (1983, 7),
+    (1985, 7),
+    (1988, 1),
+    (1990, 1),
+    (1991, 1),
+    (1992, 7),
+    (1993, 7),
+    (1994, 7),
+    (1996, 1),
+    (1997, 7),
+    (1999, 1),
+    (2006, 1),
+    (2009, 1),
+    (2012, 7),
+    (2015, 7),
+    (2017, 1),
+];
+
+/// Days since 1970-01-01 of a date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_from_march = (month + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Date of a number of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// ISO-8601 date, `None` outside of years 0 to 9999
+fn iso8601(seconds: i64, nanos: u32) -> Option<String> {
+    let (year, month, day) = civil_from_days(seconds.div_euclid(DAY));
+    if !(0..=9999).contains(&year) {
+        return None;
+    }
+    let time = seconds.rem_euclid(DAY);
+    let fraction = if nanos == 0 {
+        String::new()
+    } else if nanos.is_multiple_of(1_000_000) {
+        format!(".{:03}", nanos / 1_000_000)
+    } else if nanos.is_multiple_of(1000) {
+        format!(".{:06}", nanos / 1000)
+    } else {
+        format!(".{nanos:09}")
+    };
+    Some(format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}{fraction}Z",
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    ))
+}
+
+/// ISO-8601 date of `value` units since the unix epoch, with `per_second` units in a second
+fn from_units(value: i64, per_second: i64) -> Option<String> {
+    let nanos = u32::try_from(value.rem_euclid(per_second) * (1_000_000_000 / per_second)).ok()?;
+    iso8601(value.div_euclid(per_second), nanos)
+}
+
+/// ISO-8601 date of `value` seconds since the unix epoch
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+fn from_float(value: f64) -> Option<String> {
+    // about the year 33000
+    if !value.is_finite() || value.abs() > 1e12 {
+        return None;
+    }
+    let seconds = value.floor();
+    let nanos = (((value - seconds) * 1e9).round() as u32).min(999_999_999);
+    iso8601(seconds as i64, nanos)
+}
+
+/// Encoding of a timestamp
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Timestamp {
+    /// signed 32 bits seconds since 1970
+    Unix32,
+    /// signed 64 bits seconds since 1970
+    Unix64,
+    /// signed 64 bits milliseconds since 1970
+    UnixMillis,
+    /// signed 64 bits microseconds since 1970
+    UnixMicros,
+    /// signed 64 bits nanoseconds since 1970
+    UnixNanos,
+    /// Windows FILETIME, 100 nanoseconds since 1601
+    Filetime,
+    /// MS-DOS date and time, local time
+    Dos,
+    /// HFS+, seconds since 1904
+    Hfs,
+    /// Cocoa/Mac absolute time, float seconds since 2001
+    Cocoa,
+    /// GPS time, seconds since 1980-01-06 without leap seconds
+    Gps,
+    /// OLE automation date, float days since 1899-12-30
+    Ole,
+}
+
+impl Timestamp {
+    /// Every encoding
+    pub(crate) const ALL: [Timestamp; 11] = [
+        Timestamp::Unix32,
+        Timestamp::Unix64,
+        Timestamp::UnixMillis,
+        Timestamp::UnixMicros,
+        Timestamp::UnixNanos,
+        Timestamp::Filetime,
+        Timestamp::Dos,
+        Timestamp::Hfs,
+        Timestamp::Cocoa,
+        Timestamp::Gps,
+        Timestamp::Ole,
+    ];
+
+    /// Name of the encoding
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Timestamp::Unix32 => "Unix 32 bits (s)",
+            Timestamp::Unix64 => "Unix 64 bits (s)",
+            Timestamp::UnixMillis => "Unix 64 bits (ms)",
+            Timestamp::UnixMicros => "Unix 64 bits (µs)",
+            Timestamp::UnixNanos => "Unix 64 bits (ns)",
+            Timestamp::Filetime => "Windows FILETIME",
+            Timestamp::Dos => "DOS date/time (local)",
+            Timestamp::Hfs => "HFS+",
+            Timestamp::Cocoa => "Cocoa/Mac absolute time",
+            Timestamp::Gps => "GPS time",
+            Timestamp::Ole => "OLE automation date",
+        }
+    }
+
+    /// Size in bytes
+    pub(crate) fn size(self) -> usize {
+        match self {
+            Timestamp::Unix32 | Timestamp::Dos | Timestamp::Hfs | Timestamp::Gps => 4,
+            _ => 8,
+        }
+    }
+
+    /// ISO-8601 date stored in the first bytes of `bytes`
+    pub(crate) fn decode(self, bytes: &[u8], little_endian: bool) -> Option<String> {
+        let bytes = bytes.get(..self.size())?;
+        let mut buf = [0u8; 8];
+        buf[..bytes.len()].copy_from_slice(bytes);
+        if !little_endian {
+            buf[..bytes.len()].reverse();
+        }
+        let value = u64::from_le_bytes(buf);
+        let [b0, b1, b2, b3, ..] = buf;
+        let value32 = u32::from_le_bytes([b0, b1, b2, b3]);
+        match self {
+            Timestamp::Unix32 => iso8601(i64::from(value32.cast_signed()), 0),
+            Timestamp::Unix64 => iso8601(value.cast_signed(), 0),
+            Timestamp::UnixMillis => from_units(value.cast_signed(), 1000),
+            Timestamp::UnixMicros => from_units(value.cast_signed(), 1_000_000),
+            Timestamp::UnixNanos => from_units(value.cast_signed(), 1_000_000_000),
+            Timestamp::Filetime => {
+                let ticks = i128::from(value) - 116_444_736_000_000_000;
+                from_units(i64::try_from(ticks).ok()?, 10_000_000)
+            }
+            Timestamp::Dos => Self::decode_dos(value32),
+            Timestamp::Hfs => iso8601(i64::from(value32) - 2_082_844_800, 0),
+            Timestamp::Cocoa => from_float(f64::from_bits(value) + 978_307_200.0),
+            Timestamp::Gps => {
+                let gps = i64::from(value32);
+                let leaps = std::iter::zip(0.., LEAP_SECONDS)
+                    .filter(|(idx, (year, month))| {
+                        gps > days_from_civil(*year, *month, 1) * DAY - 315_964_800 + idx
+                    })
+                    .count();
+                iso8601(gps + 315_964_800 - i64::try_from(leaps).ok()?, 0)
+            }
+            Timestamp::Ole => {
+                // the fraction is the time of the day, even before 1899-12-30
+                let days = f64::from_bits(value);
+                let seconds = days.trunc() * 86_400.0 + days.fract().abs() * 86_400.0;
+                from_float(seconds - 2_209_161_600.0)
+            }
+        }
+    }
+
+    /// Date of a DOS date in the high word and a DOS time in the low word
+    fn decode_dos(value: u32) -> Option<String> {
+        let date = value >> 16;
+        let time = value & 0xFFFF;
+        let (year, month, day) = (1980 + (date >> 9), (date >> 5) & 0xF, date & 0x1F);
+        let (hour, minute, second) = (time >> 11, (time >> 5) & 0x3F, (time & 0x1F) * 2);
+        if !(1..=12).contains(&month) || day == 0 || hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+        Some(format!(
+            "{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}"
+        ))
+    }
+
+    /// Show the dates stored at the start of `bytes`, a click copies a date
+    pub(crate) fn ui_table(ui: &mut egui::Ui, bytes: &[u8]) {
+        egui::Grid::new("timestamps").striped(true).show(ui, |ui| {
+            ui.label("Format");
+            ui.label("Little endian");
+            ui.label("Big endian");
+            ui.end_row();
+            for timestamp in Self::ALL {
+                if timestamp.size() > bytes.len() {
+                    continue;
+                }
+                ui.label(timestamp.name());
+                for little_endian in [true, false] {
+                    match timestamp.decode(bytes, little_endian) {
+                        Some(date) => {
+                            if ui
+                                .add(egui::Label::new(&date).sense(Sense::click()))
+                                .on_hover_text("Click to copy")
+                                .clicked()
+                            {
+                                ui.ctx().copy_text(date);
+                            }
+                        }
+                        None => {
+                            ui.label("-");
+                        }
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Timestamp;
+
+    #[test]
+    fn test_unix() {
+        let decode =
+            |timestamp: Timestamp, value: i64| timestamp.decode(&value.to_le_bytes(), true);
+        assert_eq!(
+            decode(Timestamp::Unix32, 0).as_deref(),
+            Some("1970-01-01T00:00:00Z")
+        );
+        assert_eq!(
+            decode(Timestamp::Unix64, 1_700_000_000).as_deref(),
+            Some("2023-11-14T22:13:20Z")
+        );
+        assert_eq!(
+            decode(Timestamp::UnixMillis, -1).as_deref(),
+            Some("1969-12-31T23:59:59.999Z")
+        );
+        assert_eq!(
+            decode(Timestamp::UnixNanos, 1).as_deref(),
+            Some("1970-01-01T00:00:00.000000001Z")
+        );
+        assert_eq!(decode(Timestamp::Unix64, i64::MAX), None);
+        assert_eq!(Timestamp::Unix64.decode(&[0; 4], true), None);
+        let be = 1_700_000_000u32.to_be_bytes();
+        assert_eq!(
+            Timestamp::Unix32.decode(&be, false).as_deref(),
+            Some("2023-11-14T22:13:20Z")
+        );
+    }
+
+    #[test]
+    fn test_other_epochs() {
+        let filetime = 116_444_736_000_000_000u64.to_le_bytes();
+        assert_eq!(
+            Timestamp::Filetime.decode(&filetime, true).as_deref(),
+            Some("1970-01-01T00:00:00Z")
+        );
+        assert_eq!(
+            Timestamp::Dos
+                .decode(&0x5A6B_8C21u32.to_le_bytes(), true)
+                .as_deref(),
+            Some("2025-03-11T17:33:02")
+        );
+        assert_eq!(Timestamp::Dos.decode(&[0; 4], true), None);
+        assert_eq!(
+            Timestamp::Hfs.decode(&[0; 4], true).as_deref(),
+            Some("1904-01-01T00:00:00Z")
+        );
+        assert_eq!(
+            Timestamp::Cocoa
+                .decode(&0.5f64.to_le_bytes(), true)
+                .as_deref(),
+            Some("2001-01-01T00:00:00.500Z")
+        );
+        assert_eq!(
+            Timestamp::Ole
+                .decode(&1.25f64.to_le_bytes(), true)
+                .as_deref(),
+            Some("1899-12-31T06:00:00Z")
+        );
+        assert_eq!(
+            Timestamp::Ole
+                .decode(&(-1.25f64).to_le_bytes(), true)
+                .as_deref(),
+            Some("1899-12-29T06:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_gps_leap_seconds() {
+        let decode = |gps: u32| Timestamp::Gps.decode(&gps.to_le_bytes(), true);
+        assert_eq!(decode(0).as_deref(), Some("1980-01-06T00:00:00Z"));
+        assert_eq!(
+            decode(1_167_264_018).as_deref(),
+            Some("2017-01-01T00:00:00Z")
+        );
+        assert_eq!(
+            decode(1_167_264_016).as_deref(),
+            Some("2016-12-31T23:59:59Z")
+        );
+    }
+}
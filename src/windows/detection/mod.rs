@@ -4,7 +4,7 @@ mod cert;
 mod png;
 mod xml;
 
-use bladvak::eframe::egui::{self};
+use bladvak::eframe::egui::{self, Color32};
 use bladvak::errors::ErrorManager;
 use std::ops::RangeInclusive;
 
@@ -13,6 +13,43 @@ use crate::windows::detection::cert::{CertData, show_certs};
 use crate::windows::detection::png::{PngData, show_png_chunks};
 use crate::windows::detection::xml::{XmlData, xml_tree_ui};
 
+/// Byte range of a detected structure
+#[derive(Debug, Clone)]
+pub(crate) struct Region {
+    /// bytes of the structure
+    pub(crate) range: RangeInclusive<usize>,
+    /// name of the structure
+    pub(crate) name: String,
+}
+
+impl Region {
+    /// Band colors, light and dark theme
+    const COLORS: [(Color32, Color32); 4] = [
+        (
+            Color32::from_rgba_premultiplied(0, 60, 120, 40),
+            Color32::from_rgba_premultiplied(40, 90, 160, 60),
+        ),
+        (
+            Color32::from_rgba_premultiplied(0, 100, 30, 40),
+            Color32::from_rgba_premultiplied(40, 130, 60, 60),
+        ),
+        (
+            Color32::from_rgba_premultiplied(110, 0, 100, 40),
+            Color32::from_rgba_premultiplied(140, 50, 130, 60),
+        ),
+        (
+            Color32::from_rgba_premultiplied(120, 70, 0, 40),
+            Color32::from_rgba_premultiplied(150, 100, 30, 60),
+        ),
+    ];
+
+    /// Band color of the region `index`
+    pub(crate) fn color(index: usize, is_light: bool) -> Color32 {
+        let (light, dark) = Self::COLORS[index % Self::COLORS.len()];
+        if is_light { light } else { dark }
+    }
+}
+
 /// Histogram data cache
 #[derive(Debug)]
 enum DetectionCache {
@@ -46,6 +83,18 @@ impl DetectionCache {
         }
     }
 
+    /// Regions of the detected structures
+    fn regions(&self) -> Vec<Region> {
+        match self {
+            DetectionCache::Png(Some(data)) => data.regions(),
+            DetectionCache::Xml(Some(data)) => data.regions.clone(),
+            DetectionCache::Png(None)
+            | DetectionCache::Xml(None)
+            | DetectionCache::Cert(_)
+            | DetectionCache::Empty => Vec::new(),
+        }
+    }
+
     /// parse to create cache
     fn parse(binary_data: &[u8], file_info: &FileInfoData) -> DetectionCache {
         match file_info.extension.as_str() {
@@ -78,6 +127,12 @@ pub(crate) struct Detection {
 
     /// cached data
     cache: DetectionCache,
+
+    /// draw the regions on the hex view, the file is only parsed for it once enabled
+    pub(crate) overlay: bool,
+
+    /// regions of the cached data
+    regions: Vec<Region>,
 }
 
 impl Detection {
@@ -86,12 +141,27 @@ impl Detection {
         Self {
             is_open: false,
             cache: DetectionCache::Empty,
+            overlay: false,
+            regions: Vec::new(),
         }
     }
 
     /// reset data
     pub(crate) fn reset(&mut self) {
         self.cache = DetectionCache::Empty;
+        self.regions.clear();
+    }
+
+    /// Regions overlapping `range`, with their index
+    pub(crate) fn regions_in(
+        &self,
+        range: RangeInclusive<usize>,
+    ) -> impl Iterator<Item = (usize, &Region)> {
+        self.regions.iter().enumerate().filter(move |(_, region)| {
+            self.overlay
+                && region.range.start() <= range.end()
+                && range.start() <= region.range.end()
+        })
     }
 
     /// Show the detection ui
@@ -102,6 +172,10 @@ impl Detection {
         ui: &mut egui::Ui,
         _error_manager: &mut ErrorManager,
     ) -> Option<RangeInclusive<usize>> {
+        if (self.is_open || self.overlay) && matches!(self.cache, DetectionCache::Empty) {
+            self.cache = DetectionCache::parse(binary_data, file_info);
+            self.regions = self.cache.regions();
+        }
         if self.is_open {
             let mut is_open = self.is_open;
             let mut ret = None;
@@ -113,10 +187,8 @@ impl Detection {
                         "Name: {} ({}) - {}",
                         file_info.name, file_info.file_type, file_info.extension
                     ));
+                    ui.checkbox(&mut self.overlay, "Show on the hex view");
                     ui.separator();
-                    if matches!(self.cache, DetectionCache::Empty) {
-                        self.cache = DetectionCache::parse(binary_data, file_info);
-                    }
                     ret = self.cache.show(ui, binary_data, file_info);
                 });
             self.is_open = is_open;
@@ -3,6 +3,8 @@
 use bladvak::eframe::egui;
 use std::ops::RangeInclusive;
 
+use crate::windows::detection::Region;
+
 /// png chunk
 #[derive(Debug)]
 struct PngChunk {
@@ -124,6 +126,22 @@ impl PngData {
         }
         Some(png_data)
     }
+
+    /// regions of the signature and the chunks
+    pub(crate) fn regions(&self) -> Vec<Region> {
+        let mut regions = vec![Region {
+            range: 0..=(PNG_SIGNATURE.len() - 1),
+            name: "PNG signature".to_string(),
+        }];
+        for (index, chunk) in self.chunks.iter().enumerate() {
+            let crc = if chunk.crc_valid { "CRC ok" } else { "CRC bad" };
+            regions.push(Region {
+                range: chunk.start..=chunk.end,
+                name: format!("{} chunk #{index}, {crc}", chunk.chunk_type),
+            });
+        }
+        regions
+    }
 }
 /// CRC32 (IEEE) implementation
 fn crc32(data: &[u8]) -> u32 {
@@ -202,3 +220,34 @@ pub fn show_png_chunks(
         });
     return_range
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{PNG_SIGNATURE, PngData, crc32};
+
+    #[test]
+    fn test_png_regions() {
+        let mut data = PNG_SIGNATURE.to_vec();
+        data.extend_from_slice(&[0, 0, 0, 1]);
+        data.extend_from_slice(b"tEXt");
+        data.push(b'a');
+        data.extend_from_slice(&crc32(b"tEXta").to_be_bytes());
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        data.extend_from_slice(b"IEND");
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        let regions = PngData::parse(&data).map(|png| png.regions());
+        let regions = regions.unwrap_or_default();
+        let names = regions
+            .iter()
+            .map(|region| (region.range.clone(), region.name.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                (0..=7, "PNG signature"),
+                (8..=20, "tEXt chunk #0, CRC ok"),
+                (21..=32, "IEND chunk #1, CRC bad"),
+            ]
+        );
+    }
+}
@@ -5,20 +5,42 @@ use std::ops::RangeInclusive;
 use bladvak::eframe::egui::{self, CollapsingHeader};
 use roxmltree::{Document, Node};
 
+use crate::windows::detection::Region;
+
 /// xml cached data
 #[derive(Debug)]
 pub(crate) struct XmlData {
     /// inner xmld string
     inner: String,
+    /// regions of the elements
+    pub(crate) regions: Vec<Region>,
 }
 
 impl XmlData {
     /// parse the data
     pub(crate) fn parse(binary_data: &[u8]) -> Self {
         let xml_str = String::from_utf8_lossy(binary_data);
-        Self {
-            inner: xml_str.to_string(),
-        }
+        let inner = xml_str.to_string();
+        // offsets in the lossy string drift from the bytes after an invalid sequence
+        let regions = std::str::from_utf8(binary_data).map_or_else(|_| Vec::new(), Self::regions);
+        Self { inner, regions }
+    }
+
+    /// regions of the elements
+    fn regions(xml: &str) -> Vec<Region> {
+        let Ok(doc) = Document::parse(xml) else {
+            return Vec::new();
+        };
+        doc.descendants()
+            .filter(Node::is_element)
+            .filter_map(|node| {
+                let range = node.range();
+                Some(Region {
+                    range: range.start..=range.end.checked_sub(1)?,
+                    name: format!("{} element", format_element_label(node)),
+                })
+            })
+            .collect()
     }
 }
 
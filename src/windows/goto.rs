@@ -0,0 +1,261 @@
+//! Go to offset
+
+use bladvak::eframe::egui::{self, Color32, Key, KeyboardShortcut, Modifiers, RichText};
+use bladvak::errors::ErrorManager;
+
+/// Go to window data
+#[derive(Debug)]
+pub(crate) struct GoTo {
+    /// is open
+    pub(crate) is_open: bool,
+    /// offset expression
+    value: String,
+    /// parse error
+    error: Option<String>,
+    /// focus the text field on next frame
+    request_focus: bool,
+    /// previous positions
+    back: Vec<usize>,
+    /// positions left with back
+    forward: Vec<usize>,
+}
+
+impl GoTo {
+    /// New go to data
+    pub(crate) fn new() -> Self {
+        Self {
+            is_open: false,
+            value: String::new(),
+            error: None,
+            request_focus: false,
+            back: Vec::new(),
+            forward: Vec::new(),
+        }
+    }
+
+    /// reset data
+    pub(crate) fn reset(&mut self) {
+        self.error = None;
+    }
+
+    /// Remember the position left by a jump
+    pub(crate) fn remember(&mut self, current: usize) {
+        if self.back.last() != Some(&current) {
+            self.back.push(current);
+        }
+        self.forward.clear();
+    }
+
+    /// Go back to the previous position
+    fn go_back(&mut self, current: usize) -> Option<usize> {
+        let previous = self.back.pop()?;
+        self.forward.push(current);
+        Some(previous)
+    }
+
+    /// Go forward to the position left with back
+    fn go_forward(&mut self, current: usize) -> Option<usize> {
+        let next = self.forward.pop()?;
+        self.back.push(current);
+        Some(next)
+    }
+
+    /// Show the go to ui, returns the offset to jump to
+    pub(crate) fn ui(
+        &mut self,
+        current: usize,
+        file_len: usize,
+        base_address: usize,
+        ui: &mut egui::Ui,
+        _error_manager: &mut ErrorManager,
+    ) -> Option<usize> {
+        let open_shortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::G);
+        let back_shortcut = KeyboardShortcut::new(Modifiers::ALT, Key::ArrowLeft);
+        let forward_shortcut = KeyboardShortcut::new(Modifiers::ALT, Key::ArrowRight);
+        let (open, back, forward) = ui.ctx().input_mut(|i| {
+            (
+                i.consume_shortcut(&open_shortcut),
+                i.consume_shortcut(&back_shortcut),
+                i.consume_shortcut(&forward_shortcut),
+            )
+        });
+        if open {
+            self.is_open = true;
+            self.request_focus = true;
+        }
+        let mut ret = None;
+        if back {
+            ret = self.go_back(current);
+        } else if forward {
+            ret = self.go_forward(current);
+        }
+        if self.is_open {
+            let mut is_open = self.is_open;
+            egui::Window::new("Go to")
+                .open(&mut is_open)
+                .resizable(false)
+                .show(ui.ctx(), |ui| {
+                    ui.label("Offset (0x10, 16, 0o20, +0x100, -20, end-16)");
+                    if base_address != 0 {
+                        ui.label(format!("Addresses start at {base_address:#X}"));
+                    }
+                    let mut go = false;
+                    ui.horizontal(|ui| {
+                        let resp = ui.text_edit_singleline(&mut self.value);
+                        if self.request_focus {
+                            resp.request_focus();
+                            self.request_focus = false;
+                        }
+                        if resp.changed() {
+                            self.error = None;
+                        }
+                        go = resp.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+                        go |= ui.button("Go").clicked();
+                    });
+                    if go {
+                        match parse_offset(&self.value, current, file_len, base_address) {
+                            Ok(offset) => {
+                                self.remember(current);
+                                ret = Some(offset);
+                            }
+                            Err(err) => self.error = Some(err),
+                        }
+                    }
+                    if let Some(err) = &self.error {
+                        ui.label(RichText::new(err).color(Color32::LIGHT_RED));
+                    }
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(!self.back.is_empty(), egui::Button::new("Back"))
+                            .on_hover_text("Alt+Left")
+                            .clicked()
+                        {
+                            ret = self.go_back(current);
+                        }
+                        if ui
+                            .add_enabled(!self.forward.is_empty(), egui::Button::new("Forward"))
+                            .on_hover_text("Alt+Right")
+                            .clicked()
+                        {
+                            ret = self.go_forward(current);
+                        }
+                    });
+                });
+            self.is_open = is_open;
+        }
+        ret.map(|offset| offset.min(file_len.saturating_sub(1)))
+    }
+}
+
+/// Parse a number in hex (`0x`), octal (`0o`), binary (`0b`) or decimal
+fn parse_number(token: &str) -> Result<usize, String> {
+    let (digits, radix) = if let Some(hex) = token.strip_prefix("0x") {
+        (hex, 16)
+    } else if let Some(octal) = token.strip_prefix("0o") {
+        (octal, 8)
+    } else if let Some(binary) = token.strip_prefix("0b") {
+        (binary, 2)
+    } else {
+        (token, 10)
+    };
+    usize::from_str_radix(digits, radix).map_err(|_| format!("invalid number: {token}"))
+}
+
+/// Parse an offset expression
+///
+/// Terms are added or subtracted from left to right. An expression starting with a sign
+/// is relative to `current`, `end` is the length of the file.
+/// Absolute values are addresses, starting at `base_address`.
+fn parse_offset(
+    input: &str,
+    current: usize,
+    file_len: usize,
+    base_address: usize,
+) -> Result<usize, String> {
+    let expr = input.to_lowercase().replace(char::is_whitespace, "");
+    if expr.is_empty() {
+        return Err("empty offset".into());
+    }
+    let current = base_address.saturating_add(current);
+    let end = base_address.saturating_add(file_len);
+    let mut offset: Option<usize> = None;
+    let mut rest = expr.as_str();
+    while !rest.is_empty() {
+        let sign = rest.chars().next().filter(|c| *c == '+' || *c == '-');
+        if sign.is_some() {
+            rest = &rest[1..];
+        }
+        let len = rest.find(['+', '-']).unwrap_or(rest.len());
+        let (token, tail) = rest.split_at(len);
+        let value = if token == "end" {
+            end
+        } else {
+            parse_number(token)?
+        };
+        let base = offset.unwrap_or(current);
+        offset = Some(match sign {
+            None => value,
+            Some('+') => base.checked_add(value).ok_or("offset overflow")?,
+            Some(_) => base
+                .checked_sub(value)
+                .ok_or_else(|| format!("offset before start of file: {input}"))?,
+        });
+        rest = tail;
+    }
+    let address = offset.ok_or("empty offset")?;
+    let offset = address
+        .checked_sub(base_address)
+        .ok_or_else(|| format!("address {address:#X} is before the base address"))?;
+    if offset >= file_len {
+        return Err(format!(
+            "offset {offset:#X} is beyond the end of file ({file_len:#X})"
+        ));
+    }
+    Ok(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::parse_offset;
+
+    #[test]
+    fn test_absolute_offsets() {
+        assert_eq!(parse_offset("0x10", 0, 100, 0).unwrap(), 16);
+        assert_eq!(parse_offset("16", 0, 100, 0).unwrap(), 16);
+        assert_eq!(parse_offset("0o20", 0, 100, 0).unwrap(), 16);
+        assert_eq!(parse_offset(" 0X1f ", 0, 100, 0).unwrap(), 31);
+        assert_eq!(parse_offset("0x10+4", 0, 100, 0).unwrap(), 20);
+    }
+
+    #[test]
+    fn test_relative_offsets() {
+        assert_eq!(parse_offset("+0x10", 10, 100, 0).unwrap(), 26);
+        assert_eq!(parse_offset("-5", 10, 100, 0).unwrap(), 5);
+        assert!(parse_offset("-20", 10, 100, 0).is_err());
+    }
+
+    #[test]
+    fn test_end_offsets() {
+        assert!(parse_offset("end", 0, 100, 0).is_err());
+        assert_eq!(parse_offset("end-1", 0, 100, 0).unwrap(), 99);
+        assert_eq!(parse_offset("end-16", 0, 100, 0).unwrap(), 84);
+        assert_eq!(parse_offset("END - 0x10", 0, 100, 0).unwrap(), 84);
+    }
+
+    #[test]
+    fn test_base_address() {
+        assert_eq!(parse_offset("0x1010", 0, 100, 0x1000).unwrap(), 16);
+        assert_eq!(parse_offset("+4", 10, 100, 0x1000).unwrap(), 14);
+        assert_eq!(parse_offset("end-1", 0, 100, 0x1000).unwrap(), 99);
+        assert!(parse_offset("0x10", 0, 100, 0x1000).is_err());
+    }
+
+    #[test]
+    fn test_invalid_offsets() {
+        assert!(parse_offset("", 0, 100, 0).is_err());
+        assert!(parse_offset("0x100", 0, 100, 0).is_err());
+        assert!(parse_offset("12g", 0, 100, 0).is_err());
+        assert!(parse_offset("+", 0, 100, 0).is_err());
+    }
+}
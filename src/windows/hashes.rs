@@ -0,0 +1,443 @@
+//! Checksums and hashes
+
+use bladvak::eframe::egui::{self, Color32, RichText};
+use bladvak::errors::ErrorManager;
+use crc::{
+    CRC_8_MAXIM_DOW, CRC_8_SMBUS, CRC_16_ARC, CRC_16_IBM_3740, CRC_16_KERMIT, CRC_16_MODBUS,
+    CRC_16_XMODEM, CRC_32_BZIP2, CRC_32_ISCSI, CRC_32_ISO_HDLC, CRC_32_MPEG_2, CRC_64_ECMA_182,
+    CRC_64_GO_ISO, CRC_64_XZ, Crc,
+};
+use sha2::Digest;
+
+/// Hex string of a digest
+fn to_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .concat()
+}
+
+/// Hex string of a `Digest` hash of `data`
+fn digest_hex<D: Digest>(data: &[u8]) -> String {
+    to_hex(&D::digest(data))
+}
+
+/// Adler-32 checksum
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + u32::from(*byte)) % 65_521;
+        b = (b + a) % 65_521;
+    }
+    (b << 16) | a
+}
+
+/// Fletcher-16 checksum
+fn fletcher16(data: &[u8]) -> u16 {
+    let (mut sum1, mut sum2) = (0u16, 0u16);
+    for byte in data {
+        sum1 = (sum1 + u16::from(*byte)) % 255;
+        sum2 = (sum2 + sum1) % 255;
+    }
+    (sum2 << 8) | sum1
+}
+
+/// Fletcher-32 checksum of little endian 16 bits words, an odd byte is padded with zero
+fn fletcher32(data: &[u8]) -> u32 {
+    let (mut sum1, mut sum2) = (0u32, 0u32);
+    for word in data.chunks(2) {
+        let high = word.get(1).copied().unwrap_or_default();
+        sum1 = (sum1 + u32::from(u16::from_le_bytes([word[0], high]))) % 65_535;
+        sum2 = (sum2 + sum1) % 65_535;
+    }
+    (sum2 << 16) | sum1
+}
+
+/// Checksum or hash algorithm
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HashAlgorithm {
+    /// CRC-8/SMBUS
+    Crc8Smbus,
+    /// CRC-8/MAXIM-DOW
+    Crc8MaximDow,
+    /// CRC-16/ARC
+    Crc16Arc,
+    /// CRC-16/IBM-3740, also known as CCITT-FALSE
+    Crc16Ibm3740,
+    /// CRC-16/KERMIT
+    Crc16Kermit,
+    /// CRC-16/MODBUS
+    Crc16Modbus,
+    /// CRC-16/XMODEM
+    Crc16Xmodem,
+    /// CRC-32/ISO-HDLC, the zlib and PNG CRC
+    Crc32,
+    /// CRC-32/ISCSI, also known as CRC-32C
+    Crc32c,
+    /// CRC-32/BZIP2
+    Crc32Bzip2,
+    /// CRC-32/MPEG-2
+    Crc32Mpeg2,
+    /// CRC-64/ECMA-182
+    Crc64Ecma,
+    /// CRC-64/XZ
+    Crc64Xz,
+    /// CRC-64/GO-ISO
+    Crc64GoIso,
+    /// Adler-32
+    Adler32,
+    /// Fletcher-16
+    Fletcher16,
+    /// Fletcher-32
+    Fletcher32,
+    /// MD5
+    Md5,
+    /// SHA-1
+    Sha1,
+    /// SHA-224
+    Sha224,
+    /// SHA-256
+    Sha256,
+    /// SHA-384
+    Sha384,
+    /// SHA-512
+    Sha512,
+    /// SHA3-224
+    Sha3_224,
+    /// SHA3-256
+    Sha3_256,
+    /// SHA3-384
+    Sha3_384,
+    /// SHA3-512
+    Sha3_512,
+    /// BLAKE3
+    Blake3,
+    /// XXH32
+    Xxh32,
+    /// XXH64
+    Xxh64,
+    /// XXH3 64 bits
+    Xxh3_64,
+    /// XXH3 128 bits
+    Xxh3_128,
+}
+
+impl HashAlgorithm {
+    /// Every algorithm
+    const ALL: [HashAlgorithm; 32] = [
+        HashAlgorithm::Crc8Smbus,
+        HashAlgorithm::Crc8MaximDow,
+        HashAlgorithm::Crc16Arc,
+        HashAlgorithm::Crc16Ibm3740,
+        HashAlgorithm::Crc16Kermit,
+        HashAlgorithm::Crc16Modbus,
+        HashAlgorithm::Crc16Xmodem,
+        HashAlgorithm::Crc32,
+        HashAlgorithm::Crc32c,
+        HashAlgorithm::Crc32Bzip2,
+        HashAlgorithm::Crc32Mpeg2,
+        HashAlgorithm::Crc64Ecma,
+        HashAlgorithm::Crc64Xz,
+        HashAlgorithm::Crc64GoIso,
+        HashAlgorithm::Adler32,
+        HashAlgorithm::Fletcher16,
+        HashAlgorithm::Fletcher32,
+        HashAlgorithm::Md5,
+        HashAlgorithm::Sha1,
+        HashAlgorithm::Sha224,
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Sha384,
+        HashAlgorithm::Sha512,
+        HashAlgorithm::Sha3_224,
+        HashAlgorithm::Sha3_256,
+        HashAlgorithm::Sha3_384,
+        HashAlgorithm::Sha3_512,
+        HashAlgorithm::Blake3,
+        HashAlgorithm::Xxh32,
+        HashAlgorithm::Xxh64,
+        HashAlgorithm::Xxh3_64,
+        HashAlgorithm::Xxh3_128,
+    ];
+
+    /// Name of the algorithm
+    fn name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Crc8Smbus => "CRC-8/SMBUS",
+            HashAlgorithm::Crc8MaximDow => "CRC-8/MAXIM-DOW",
+            HashAlgorithm::Crc16Arc => "CRC-16/ARC",
+            HashAlgorithm::Crc16Ibm3740 => "CRC-16/CCITT-FALSE",
+            HashAlgorithm::Crc16Kermit => "CRC-16/KERMIT",
+            HashAlgorithm::Crc16Modbus => "CRC-16/MODBUS",
+            HashAlgorithm::Crc16Xmodem => "CRC-16/XMODEM",
+            HashAlgorithm::Crc32 => "CRC-32",
+            HashAlgorithm::Crc32c => "CRC-32C",
+            HashAlgorithm::Crc32Bzip2 => "CRC-32/BZIP2",
+            HashAlgorithm::Crc32Mpeg2 => "CRC-32/MPEG-2",
+            HashAlgorithm::Crc64Ecma => "CRC-64/ECMA-182",
+            HashAlgorithm::Crc64Xz => "CRC-64/XZ",
+            HashAlgorithm::Crc64GoIso => "CRC-64/GO-ISO",
+            HashAlgorithm::Adler32 => "Adler-32",
+            HashAlgorithm::Fletcher16 => "Fletcher-16",
+            HashAlgorithm::Fletcher32 => "Fletcher-32",
+            HashAlgorithm::Md5 => "MD5",
+            HashAlgorithm::Sha1 => "SHA-1",
+            HashAlgorithm::Sha224 => "SHA-224",
+            HashAlgorithm::Sha256 => "SHA-256",
+            HashAlgorithm::Sha384 => "SHA-384",
+            HashAlgorithm::Sha512 => "SHA-512",
+            HashAlgorithm::Sha3_224 => "SHA3-224",
+            HashAlgorithm::Sha3_256 => "SHA3-256",
+            HashAlgorithm::Sha3_384 => "SHA3-384",
+            HashAlgorithm::Sha3_512 => "SHA3-512",
+            HashAlgorithm::Blake3 => "BLAKE3",
+            HashAlgorithm::Xxh32 => "XXH32",
+            HashAlgorithm::Xxh64 => "XXH64",
+            HashAlgorithm::Xxh3_64 => "XXH3-64",
+            HashAlgorithm::Xxh3_128 => "XXH3-128",
+        }
+    }
+
+    /// Hex value of the checksum or hash of `data`
+    fn compute(self, data: &[u8]) -> String {
+        match self {
+            HashAlgorithm::Crc8Smbus => {
+                format!("{:02x}", Crc::<u8>::new(&CRC_8_SMBUS).checksum(data))
+            }
+            HashAlgorithm::Crc8MaximDow => {
+                format!("{:02x}", Crc::<u8>::new(&CRC_8_MAXIM_DOW).checksum(data))
+            }
+            HashAlgorithm::Crc16Arc => {
+                format!("{:04x}", Crc::<u16>::new(&CRC_16_ARC).checksum(data))
+            }
+            HashAlgorithm::Crc16Ibm3740 => {
+                format!("{:04x}", Crc::<u16>::new(&CRC_16_IBM_3740).checksum(data))
+            }
+            HashAlgorithm::Crc16Kermit => {
+                format!("{:04x}", Crc::<u16>::new(&CRC_16_KERMIT).checksum(data))
+            }
+            HashAlgorithm::Crc16Modbus => {
+                format!("{:04x}", Crc::<u16>::new(&CRC_16_MODBUS).checksum(data))
+            }
+            HashAlgorithm::Crc16Xmodem => {
+                format!("{:04x}", Crc::<u16>::new(&CRC_16_XMODEM).checksum(data))
+            }
+            HashAlgorithm::Crc32 => {
+                format!("{:08x}", Crc::<u32>::new(&CRC_32_ISO_HDLC).checksum(data))
+            }
+            HashAlgorithm::Crc32c => {
+                format!("{:08x}", Crc::<u32>::new(&CRC_32_ISCSI).checksum(data))
+            }
+            HashAlgorithm::Crc32Bzip2 => {
+                format!("{:08x}", Crc::<u32>::new(&CRC_32_BZIP2).checksum(data))
+            }
+            HashAlgorithm::Crc32Mpeg2 => {
+                format!("{:08x}", Crc::<u32>::new(&CRC_32_MPEG_2).checksum(data))
+            }
+            HashAlgorithm::Crc64Ecma => {
+                format!("{:016x}", Crc::<u64>::new(&CRC_64_ECMA_182).checksum(data))
+            }
+            HashAlgorithm::Crc64Xz => {
+                format!("{:016x}", Crc::<u64>::new(&CRC_64_XZ).checksum(data))
+            }
+            HashAlgorithm::Crc64GoIso => {
+                format!("{:016x}", Crc::<u64>::new(&CRC_64_GO_ISO).checksum(data))
+            }
+            HashAlgorithm::Adler32 => format!("{:08x}", adler32(data)),
+            HashAlgorithm::Fletcher16 => format!("{:04x}", fletcher16(data)),
+            HashAlgorithm::Fletcher32 => format!("{:08x}", fletcher32(data)),
+            HashAlgorithm::Md5 => digest_hex::<md5::Md5>(data),
+            HashAlgorithm::Sha1 => digest_hex::<sha1::Sha1>(data),
+            HashAlgorithm::Sha224 => digest_hex::<sha2::Sha224>(data),
+            HashAlgorithm::Sha256 => digest_hex::<sha2::Sha256>(data),
+            HashAlgorithm::Sha384 => digest_hex::<sha2::Sha384>(data),
+            HashAlgorithm::Sha512 => digest_hex::<sha2::Sha512>(data),
+            HashAlgorithm::Sha3_224 => digest_hex::<sha3::Sha3_224>(data),
+            HashAlgorithm::Sha3_256 => digest_hex::<sha3::Sha3_256>(data),
+            HashAlgorithm::Sha3_384 => digest_hex::<sha3::Sha3_384>(data),
+            HashAlgorithm::Sha3_512 => digest_hex::<sha3::Sha3_512>(data),
+            HashAlgorithm::Blake3 => blake3::hash(data).to_hex().to_string(),
+            HashAlgorithm::Xxh32 => format!("{:08x}", xxhash_rust::xxh32::xxh32(data, 0)),
+            HashAlgorithm::Xxh64 => format!("{:016x}", xxhash_rust::xxh64::xxh64(data, 0)),
+            HashAlgorithm::Xxh3_64 => format!("{:016x}", xxhash_rust::xxh3::xxh3_64(data)),
+            HashAlgorithm::Xxh3_128 => format!("{:032x}", xxhash_rust::xxh3::xxh3_128(data)),
+        }
+    }
+}
+
+/// Expected value in the form of the computed ones
+fn normalize_expected(expected: &str) -> String {
+    let expected = expected.trim();
+    let expected = expected
+        .strip_prefix("0x")
+        .or_else(|| expected.strip_prefix("0X"))
+        .unwrap_or(expected);
+    expected
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ':' && *c != '-')
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Hashes window data
+#[derive(Debug)]
+pub(crate) struct Hashes {
+    /// is open
+    pub(crate) is_open: bool,
+    /// hash the whole file instead of the selection
+    whole_file: bool,
+    /// computed values
+    results: Vec<(HashAlgorithm, String)>,
+    /// description of the hashed bytes
+    hashed: Option<String>,
+    /// value to verify
+    expected: String,
+}
+
+impl Hashes {
+    /// New hashes data
+    pub(crate) fn new() -> Self {
+        Self {
+            is_open: false,
+            whole_file: false,
+            results: Vec::new(),
+            hashed: None,
+            expected: String::new(),
+        }
+    }
+
+    /// reset data
+    pub(crate) fn reset(&mut self) {
+        self.results.clear();
+        self.hashed = None;
+    }
+
+    /// Compute every hash of the selection, or of the file without a selection
+    fn compute(&mut self, binary_data: &[u8], ranges: &[(usize, usize)]) {
+        let selected;
+        let (data, hashed) = if self.whole_file || ranges.is_empty() {
+            (
+                binary_data,
+                format!("the whole file ({} bytes)", binary_data.len()),
+            )
+        } else {
+            selected = ranges
+                .iter()
+                .filter_map(|(start, end)| binary_data.get(*start..=*end))
+                .flatten()
+                .copied()
+                .collect::<Vec<u8>>();
+            let hashed = format!("{} selected bytes", selected.len());
+            (selected.as_slice(), hashed)
+        };
+        self.results = HashAlgorithm::ALL
+            .into_iter()
+            .map(|algorithm| (algorithm, algorithm.compute(data)))
+            .collect();
+        self.hashed = Some(hashed);
+    }
+
+    /// Show the hashes ui
+    pub(crate) fn ui(
+        &mut self,
+        binary_data: &[u8],
+        ranges: &[(usize, usize)],
+        ui: &mut egui::Ui,
+        _error_manager: &mut ErrorManager,
+    ) {
+        if !self.is_open {
+            return;
+        }
+        let mut is_open = self.is_open;
+        egui::Window::new("Hashes")
+            .open(&mut is_open)
+            .vscroll(true)
+            .show(ui.ctx(), |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Hash:");
+                    ui.selectable_value(&mut self.whole_file, false, "Selection");
+                    ui.selectable_value(&mut self.whole_file, true, "Whole file");
+                    if ui.button("Compute").clicked() {
+                        self.compute(binary_data, ranges);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Expected:");
+                    ui.text_edit_singleline(&mut self.expected)
+                        .on_hover_text("Value to verify against every hash");
+                });
+                let Some(hashed) = &self.hashed else {
+                    return;
+                };
+                let expected = normalize_expected(&self.expected);
+                let matched = self
+                    .results
+                    .iter()
+                    .filter(|(_, value)| *value == expected)
+                    .map(|(algorithm, _)| algorithm.name())
+                    .collect::<Vec<_>>();
+                if !expected.is_empty() {
+                    if matched.is_empty() {
+                        ui.label(RichText::new("No hash matches").color(Color32::LIGHT_RED));
+                    } else {
+                        ui.label(
+                            RichText::new(format!("Matches {}", matched.join(", ")))
+                                .color(Color32::GREEN),
+                        );
+                    }
+                }
+                ui.label(format!("Hashes of {hashed}"));
+                egui::Grid::new("hashes").striped(true).show(ui, |ui| {
+                    for (algorithm, value) in &self.results {
+                        ui.label(algorithm.name());
+                        let mut text = RichText::new(value).monospace();
+                        if *value == expected {
+                            text = text.color(Color32::GREEN);
+                        }
+                        ui.label(text);
+                        if ui.small_button("Copy").clicked() {
+                            ui.ctx().copy_text(value.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        self.is_open = is_open;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HashAlgorithm, adler32, fletcher16, fletcher32, normalize_expected};
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+        assert_eq!(fletcher16(b"abcde"), 0xC8F0);
+        assert_eq!(fletcher32(b"abcde"), 0xF04F_C729);
+        let check = b"123456789";
+        for (algorithm, value) in [
+            (HashAlgorithm::Crc8Smbus, "f4"),
+            (HashAlgorithm::Crc16Arc, "bb3d"),
+            (HashAlgorithm::Crc16Ibm3740, "29b1"),
+            (HashAlgorithm::Crc32, "cbf43926"),
+            (HashAlgorithm::Crc32c, "e3069283"),
+            (HashAlgorithm::Crc64Xz, "995dc9bbdf1939fa"),
+        ] {
+            assert_eq!(algorithm.compute(check), value, "{}", algorithm.name());
+        }
+    }
+
+    #[test]
+    fn test_hashes() {
+        assert_eq!(
+            HashAlgorithm::Md5.compute(b""),
+            "d41d8cd98f00b204e9800998ecf8427e"
+        );
+        assert_eq!(
+            HashAlgorithm::Sha256.compute(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(normalize_expected(" 0xBA:78 16bf "), "ba7816bf".to_string());
+    }
+}
@@ -0,0 +1,128 @@
+//! History window
+
+use bladvak::eframe::egui;
+use bladvak::errors::ErrorManager;
+use std::ops::RangeInclusive;
+
+use crate::history::{Edit, History};
+
+/// Action requested from the history window
+#[derive(Debug)]
+pub(crate) enum HistoryAction {
+    /// undo last edit
+    Undo,
+    /// redo last undone edit
+    Redo,
+    /// select a range
+    Show(RangeInclusive<usize>),
+}
+
+/// History window data
+#[derive(Debug)]
+pub(crate) struct HistoryWindow {
+    /// is open
+    pub(crate) is_open: bool,
+}
+
+impl HistoryWindow {
+    /// New history window
+    pub(crate) fn new() -> Self {
+        Self { is_open: false }
+    }
+
+    /// Show the history ui
+    pub(crate) fn ui(
+        &mut self,
+        history: &History,
+        ui: &mut egui::Ui,
+        _error_manager: &mut ErrorManager,
+    ) -> Option<HistoryAction> {
+        if self.is_open {
+            let mut is_open = self.is_open;
+            let mut ret = None;
+            egui::Window::new("History")
+                .open(&mut is_open)
+                .vscroll(true)
+                .show(ui.ctx(), |ui| {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(!history.undo_stack.is_empty(), egui::Button::new("Undo"))
+                            .on_hover_text("Ctrl+Z")
+                            .clicked()
+                        {
+                            ret = Some(HistoryAction::Undo);
+                        }
+                        if ui
+                            .add_enabled(!history.redo_stack.is_empty(), egui::Button::new("Redo"))
+                            .on_hover_text("Ctrl+Shift+Z")
+                            .clicked()
+                        {
+                            ret = Some(HistoryAction::Redo);
+                        }
+                    });
+                    ui.separator();
+                    if history.undo_stack.is_empty() && history.redo_stack.is_empty() {
+                        ui.label("No edit");
+                        return;
+                    }
+                    egui::Grid::new("history_table")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("Index");
+                            ui.label("Operation");
+                            ui.label("Range");
+                            ui.label("Size");
+                            ui.end_row();
+                            for (index, edit) in history.undo_stack.iter().enumerate() {
+                                if let Some(action) = Self::ui_edit_row(ui, index, edit, false) {
+                                    ret = Some(action);
+                                }
+                            }
+                            let nb_done = history.undo_stack.len();
+                            for (index, edit) in history.redo_stack.iter().rev().enumerate() {
+                                if let Some(action) =
+                                    Self::ui_edit_row(ui, nb_done + index, edit, true)
+                                {
+                                    ret = Some(action);
+                                }
+                            }
+                        });
+                });
+            self.is_open = is_open;
+            return ret;
+        }
+        None
+    }
+
+    /// Show one edit of the history
+    fn ui_edit_row(
+        ui: &mut egui::Ui,
+        index: usize,
+        edit: &Edit,
+        is_undone: bool,
+    ) -> Option<HistoryAction> {
+        let mut ret = None;
+        let text_color = if is_undone {
+            ui.visuals().weak_text_color()
+        } else {
+            ui.visuals().text_color()
+        };
+        ui.colored_label(text_color, index.to_string());
+        ui.colored_label(text_color, edit.kind.name());
+        if let Some(range) = edit.range() {
+            ui.colored_label(
+                text_color,
+                format!("{:08X}-{:08X}", range.start(), range.end()),
+            );
+            ui.colored_label(text_color, edit.description());
+            if !is_undone && ui.button("Show").clicked() {
+                ret = Some(HistoryAction::Show(range));
+            }
+        } else {
+            ui.colored_label(text_color, "-");
+            ui.colored_label(text_color, edit.description());
+        }
+        ui.end_row();
+        ret
+    }
+}
@@ -110,7 +110,25 @@ impl Importer {
 }
 
 /// Parse a hex string
-fn parse_hex_string(input: &str) -> Result<Vec<u8>, String> {
+pub(crate) fn parse_hex_string(input: &str) -> Result<Vec<u8>, String> {
+    let hex_digits = normalize_hex_digits(input, false)?;
+
+    // Parse pairs into u8
+    let bytes = (0..hex_digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex_digits[i..i + 2], 16)
+                .map_err(|_| format!("invalid hex byte: {}", &hex_digits[i..i + 2]))
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+
+    Ok(bytes)
+}
+
+/// Normalize a hex string into a contiguous string of hex digits
+///
+/// When `allow_wildcards` is set, `?` is kept as a wildcard digit
+pub(crate) fn normalize_hex_digits(input: &str, allow_wildcards: bool) -> Result<String, String> {
     // 1. Normalize input into a contiguous string of hex digits
     let mut hex_digits = String::with_capacity(input.len());
 
@@ -144,6 +162,9 @@ fn parse_hex_string(input: &str) -> Result<Vec<u8>, String> {
             // Valid hex digit
             '0'..='9' | 'a'..='f' | 'A'..='F' => hex_digits.push(c),
 
+            // Wildcard digit
+            '?' if allow_wildcards => hex_digits.push(c),
+
             _ => return Err(format!("Invalid character in input: {c}")),
         }
     }
@@ -153,16 +174,7 @@ fn parse_hex_string(input: &str) -> Result<Vec<u8>, String> {
         return Err("hex string has odd number of digits".into());
     }
 
-    // 3. Parse pairs into u8
-    let bytes = (0..hex_digits.len())
-        .step_by(2)
-        .map(|i| {
-            u8::from_str_radix(&hex_digits[i..i + 2], 16)
-                .map_err(|_| format!("invalid hex byte: {}", &hex_digits[i..i + 2]))
-        })
-        .collect::<Result<Vec<u8>, String>>()?;
-
-    Ok(bytes)
+    Ok(hex_digits)
 }
 
 /// Parse a binary string like "0b00000001 0b00000010" into Vec<u8>
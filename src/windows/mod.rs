@@ -1,17 +1,27 @@
 //! Wombat windows
 
 mod detection;
+mod goto;
+mod hashes;
 mod histogram;
+mod history;
 mod importer;
+mod search;
 
-use crate::{WombatApp, panels::FileInfoData};
+use crate::{WombatApp, data_source::DataSource, panels::FileInfoData};
 
 use bladvak::{ErrorManager, eframe::egui};
+use std::path::PathBuf;
 
 use detection::Detection;
+pub(crate) use detection::Region;
 use file_format::FileFormat;
+use goto::GoTo;
+use hashes::Hashes;
 use histogram::Histogram;
+use history::{HistoryAction, HistoryWindow};
 use importer::Importer;
+use search::{Search, SearchAction};
 
 /// File info
 #[derive(Debug)]
@@ -22,6 +32,14 @@ pub struct WindowsData {
     pub(crate) importer: Importer,
     /// detection
     pub(crate) detection: Detection,
+    /// history
+    pub(crate) history: HistoryWindow,
+    /// go to offset
+    pub(crate) goto: GoTo,
+    /// search
+    pub(crate) search: Search,
+    /// checksums and hashes
+    pub(crate) hashes: Hashes,
 }
 
 impl WindowsData {
@@ -31,6 +49,10 @@ impl WindowsData {
             histogram: Histogram::new(),
             importer: Importer::new(),
             detection: Detection::new(),
+            history: HistoryWindow::new(),
+            goto: GoTo::new(),
+            search: Search::new(),
+            hashes: Hashes::new(),
         }
     }
 
@@ -39,6 +61,9 @@ impl WindowsData {
         self.histogram.reset();
         self.importer.reset();
         self.detection.reset();
+        self.goto.reset();
+        // the search results follow the edits, see `Search::shift`
+        self.hashes.reset();
     }
 
     /// Ui top bar
@@ -46,24 +71,85 @@ impl WindowsData {
         ui.toggle_value(&mut self.histogram.is_open, "Histogram");
         ui.toggle_value(&mut self.importer.is_open, "Import");
         ui.toggle_value(&mut self.detection.is_open, "Detection");
+        ui.toggle_value(&mut self.history.is_open, "History");
+        ui.toggle_value(&mut self.goto.is_open, "Go to")
+            .on_hover_text("Ctrl+G");
+        ui.toggle_value(&mut self.search.is_open, "Find");
+        ui.toggle_value(&mut self.hashes.is_open, "Hashes");
     }
 }
 
 impl WombatApp {
     /// Display windows
     pub(crate) fn ui_windows(&mut self, ui: &mut egui::Ui, error_manager: &mut ErrorManager) {
-        use bladvak::{BladvakApp, File};
-        use std::path::PathBuf;
         self.windows_data
             .histogram
             .ui(&self.binary_file, ui, error_manager);
-        if let Some(data) = self.windows_data.importer.ui(ui, error_manager)
-            && let Err(e) = self.handle_file(File {
-                data,
-                path: PathBuf::from("imported.bin"),
-            })
+        self.windows_data.hashes.ui(
+            &self.binary_file,
+            &self.selection.ranges(),
+            ui,
+            error_manager,
+        );
+        if let Some(data) = self.windows_data.importer.ui(ui, error_manager) {
+            self.open_document(DataSource::from(data), PathBuf::from("imported.bin"));
+            // the imported bytes are not saved anywhere yet
+            self.history.mark_unsaved();
+            self.dirty = true;
+        }
+        match self
+            .windows_data
+            .history
+            .ui(&self.history, ui, error_manager)
         {
-            error_manager.add_error(e);
+            Some(HistoryAction::Undo) => {
+                if let Err(err) = self.undo() {
+                    error_manager.add_error(err);
+                }
+            }
+            Some(HistoryAction::Redo) => {
+                if let Err(err) = self.redo() {
+                    error_manager.add_error(err);
+                }
+            }
+            Some(HistoryAction::Show(range)) => {
+                self.selection.range = Some((*range.start(), *range.end()));
+                self.selection.clamp(self.binary_file.len());
+            }
+            None => {}
+        }
+        if let Some(offset) = self.windows_data.goto.ui(
+            self.selection.position(),
+            self.binary_file.len(),
+            self.display_settings.offset_format.base_address,
+            ui,
+            error_manager,
+        ) {
+            self.selection.select_offset(offset);
+        }
+        match self.windows_data.search.ui(
+            &self.binary_file,
+            self.selection.position(),
+            ui,
+            error_manager,
+        ) {
+            Some(SearchAction::Select(found)) => {
+                self.windows_data.goto.remember(self.selection.position());
+                self.selection.select_range(found.start, found.end - 1);
+            }
+            Some(SearchAction::Replace { edit, select }) => match self.apply_edit(edit) {
+                Err(err) => error_manager.add_error(err),
+                Ok(false) => {}
+                Ok(true) => {
+                    self.selection.clamp(self.binary_file.len());
+                    if let Some(replaced) = select {
+                        self.selection
+                            .select_range(replaced.start, replaced.end - 1);
+                    }
+                    self.windows_data.search.search(&self.binary_file);
+                }
+            },
+            None => {}
         }
         if self.file_format.is_none() {
             let file_fmt = FileFormat::from_bytes(&self.binary_file);
@@ -0,0 +1,709 @@
+//! Search
+
+use bladvak::eframe::egui::{self, Color32, Key, RichText, ScrollArea};
+use bladvak::errors::ErrorManager;
+use regex::bytes::{Regex, RegexBuilder};
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::history::Edit;
+use crate::identifiers::Identifier;
+use crate::windows::importer::{normalize_hex_digits, parse_hex_string};
+
+/// Maximum number of results kept
+const MAX_RESULTS: usize = 100_000;
+
+/// Number of bytes shown around a result
+const PREVIEW_CONTEXT: usize = 8;
+
+/// Search mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SearchMode {
+    /// hex bytes with wildcards
+    Hex,
+    /// text in an encoding
+    Text,
+    /// byte regex
+    Regex,
+    /// GUID, address or port written as text
+    Value,
+}
+
+/// Text encoding of a searched text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum TextEncoding {
+    /// ASCII
+    Ascii,
+    /// UTF-8
+    Utf8,
+    /// UTF-16 little endian
+    Utf16Le,
+    /// UTF-16 big endian
+    Utf16Be,
+    /// UTF-32 little endian
+    Utf32Le,
+    /// UTF-32 big endian
+    Utf32Be,
+}
+
+impl TextEncoding {
+    /// Every encoding
+    const ALL: [TextEncoding; 6] = [
+        TextEncoding::Ascii,
+        TextEncoding::Utf8,
+        TextEncoding::Utf16Le,
+        TextEncoding::Utf16Be,
+        TextEncoding::Utf32Le,
+        TextEncoding::Utf32Be,
+    ];
+
+    /// Name of the encoding
+    fn name(self) -> &'static str {
+        match self {
+            TextEncoding::Ascii => "ASCII",
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf16Le => "UTF-16LE",
+            TextEncoding::Utf16Be => "UTF-16BE",
+            TextEncoding::Utf32Le => "UTF-32LE",
+            TextEncoding::Utf32Be => "UTF-32BE",
+        }
+    }
+
+    /// Encode a char
+    fn encode(self, c: char) -> Result<Vec<u8>, String> {
+        let mut buf = [0u16; 2];
+        Ok(match self {
+            TextEncoding::Ascii => {
+                if !c.is_ascii() {
+                    return Err(format!("not an ASCII character: {c}"));
+                }
+                vec![u8::try_from(c).unwrap_or_default()]
+            }
+            TextEncoding::Utf8 => c.to_string().into_bytes(),
+            TextEncoding::Utf16Le => c
+                .encode_utf16(&mut buf)
+                .iter()
+                .flat_map(|unit| unit.to_le_bytes())
+                .collect(),
+            TextEncoding::Utf16Be => c
+                .encode_utf16(&mut buf)
+                .iter()
+                .flat_map(|unit| unit.to_be_bytes())
+                .collect(),
+            TextEncoding::Utf32Le => u32::from(c).to_le_bytes().to_vec(),
+            TextEncoding::Utf32Be => u32::from(c).to_be_bytes().to_vec(),
+        })
+    }
+}
+
+/// Build a byte regex matching `text` encoded with `encoding`
+fn text_regex(text: &str, encoding: TextEncoding, case_insensitive: bool) -> Result<Regex, String> {
+    if text.is_empty() {
+        return Err("empty text".into());
+    }
+    let mut pattern = String::from("(?s-u)");
+    for c in text.chars() {
+        let mut variants = vec![c];
+        if case_insensitive {
+            let mut lower = c.to_lowercase();
+            let mut upper = c.to_uppercase();
+            // only simple case mappings keep the same number of chars
+            if let (Some(l), None) = (lower.next(), lower.next()) {
+                variants.push(l);
+            }
+            if let (Some(u), None) = (upper.next(), upper.next()) {
+                variants.push(u);
+            }
+            variants.sort_unstable();
+            variants.dedup();
+        }
+        pattern.push_str("(?:");
+        for (idx, variant) in variants.into_iter().enumerate() {
+            if idx > 0 {
+                pattern.push('|');
+            }
+            for byte in encoding.encode(variant)? {
+                write!(pattern, "\\x{byte:02X}").map_err(|e| e.to_string())?;
+            }
+        }
+        pattern.push(')');
+    }
+    Regex::new(&pattern).map_err(|e| e.to_string())
+}
+
+/// Find every match of a regex, empty matches excluded
+fn find_regex(data: &[u8], regex: &Regex, limit: usize) -> Vec<Range<usize>> {
+    regex
+        .find_iter(data)
+        .filter(|found| !found.is_empty())
+        .map(|found| found.range())
+        .take(limit)
+        .collect()
+}
+
+/// Keep the results that do not overlap a previous one
+fn non_overlapping(matches: &[Range<usize>]) -> Vec<Range<usize>> {
+    let mut end = 0;
+    matches
+        .iter()
+        .filter(|found| {
+            let keep = found.start >= end;
+            if keep {
+                end = found.end;
+            }
+            keep
+        })
+        .cloned()
+        .collect()
+}
+
+/// Action requested from the search window
+#[derive(Debug)]
+pub(crate) enum SearchAction {
+    /// select a result
+    Select(Range<usize>),
+    /// apply a replacement
+    Replace {
+        /// replacement edit
+        edit: Edit,
+        /// range to select once replaced
+        select: Option<Range<usize>>,
+    },
+}
+
+/// Printable preview of the bytes around a result
+fn preview(data: &[u8], found: &Range<usize>) -> String {
+    let to_text = |bytes: &[u8]| -> String {
+        bytes
+            .iter()
+            .map(|b| {
+                if b.is_ascii_graphic() || *b == b' ' {
+                    char::from(*b)
+                } else {
+                    '.'
+                }
+            })
+            .collect()
+    };
+    let start = found.start.saturating_sub(PREVIEW_CONTEXT);
+    let end = (found.end + PREVIEW_CONTEXT).min(data.len());
+    let (Some(before), Some(inner), Some(after)) = (
+        data.get(start..found.start),
+        data.get(found.clone()),
+        data.get(found.end..end),
+    ) else {
+        return String::new();
+    };
+    format!("{}[{}]{}", to_text(before), to_text(inner), to_text(after))
+}
+
+/// Byte of a search pattern
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PatternByte {
+    /// expected bits
+    value: u8,
+    /// bits to compare
+    mask: u8,
+}
+
+impl PatternByte {
+    /// Does the byte match the pattern
+    fn matches(self, byte: u8) -> bool {
+        byte & self.mask == self.value
+    }
+}
+
+/// Parse a hex pattern, `??` matches any byte and `4?` any byte with a high nibble of 4
+pub(crate) fn parse_hex_pattern(input: &str) -> Result<Vec<PatternByte>, String> {
+    let hex_digits = normalize_hex_digits(input, true)?;
+    if hex_digits.is_empty() {
+        return Err("empty pattern".into());
+    }
+    let pattern = hex_digits
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            pair.iter()
+                .fold(PatternByte { value: 0, mask: 0 }, |acc, c| {
+                    let (value, mask) = match char::from(*c).to_digit(16) {
+                        Some(digit) => (u8::try_from(digit).unwrap_or_default(), 0xF),
+                        None => (0, 0),
+                    };
+                    PatternByte {
+                        value: (acc.value << 4) | value,
+                        mask: (acc.mask << 4) | mask,
+                    }
+                })
+        })
+        .collect();
+    Ok(pattern)
+}
+
+/// Pattern matching the bytes of a value
+fn value_pattern(text: &str, identifier: Identifier) -> Result<Vec<PatternByte>, String> {
+    Ok(identifier
+        .encode(text)?
+        .into_iter()
+        .map(|value| PatternByte { value, mask: 0xFF })
+        .collect())
+}
+
+/// Find every occurrence of the pattern, overlapping ones included
+fn find_pattern(data: &[u8], pattern: &[PatternByte], limit: usize) -> Vec<Range<usize>> {
+    if pattern.is_empty() || pattern.len() > data.len() {
+        return vec![];
+    }
+    data.windows(pattern.len())
+        .enumerate()
+        .filter(|(_, window)| {
+            std::iter::zip(pattern, *window).all(|(pattern_byte, byte)| pattern_byte.matches(*byte))
+        })
+        .map(|(start, _)| start..start + pattern.len())
+        .take(limit)
+        .collect()
+}
+
+/// Search window data
+#[derive(Debug)]
+pub(crate) struct Search {
+    /// is open
+    pub(crate) is_open: bool,
+    /// searched pattern
+    query: String,
+    /// search mode
+    mode: SearchMode,
+    /// encoding of the searched text
+    encoding: TextEncoding,
+    /// kind of the searched value
+    identifier: Identifier,
+    /// ignore case in text and regex modes
+    case_insensitive: bool,
+    /// replacement
+    replacement: String,
+    /// replacement is hex instead of text
+    replace_as_hex: bool,
+    /// number of replacements made by the last replace
+    replaced: Option<usize>,
+    /// pattern error
+    error: Option<String>,
+    /// results, sorted by start
+    matches: Vec<Range<usize>>,
+    /// index of the current result
+    current: Option<usize>,
+    /// results highlight color (light theme, dark theme)
+    pub(crate) color: (Color32, Color32),
+}
+
+impl Search {
+    /// New search data
+    pub(crate) fn new() -> Self {
+        Self {
+            is_open: false,
+            query: String::new(),
+            mode: SearchMode::Hex,
+            encoding: TextEncoding::Utf8,
+            identifier: Identifier::Ipv4,
+            case_insensitive: false,
+            replacement: String::new(),
+            replace_as_hex: false,
+            replaced: None,
+            error: None,
+            matches: Vec::new(),
+            current: None,
+            color: (
+                Color32::from_rgba_unmultiplied(0, 120, 255, 60),
+                Color32::from_rgba_unmultiplied(0, 160, 255, 70),
+            ),
+        }
+    }
+
+    /// Move the results after `edit` is applied, or reverted,
+    /// the results touched by the edit are dropped
+    pub(crate) fn shift(&mut self, edit: &Edit, reverted: bool) {
+        let mut current = self.current.and_then(|idx| self.matches.get(idx)).cloned();
+        for (offset, removed, inserted) in edit.shifts(reverted) {
+            let shift = |found: &Range<usize>| {
+                if found.end <= offset {
+                    Some(found.clone())
+                } else if found.start >= offset + removed {
+                    Some(found.start - removed + inserted..found.end - removed + inserted)
+                } else {
+                    None
+                }
+            };
+            self.matches = self.matches.iter().filter_map(shift).collect();
+            current = current.as_ref().and_then(shift);
+        }
+        self.current = current.and_then(|current| {
+            self.matches
+                .binary_search_by_key(&current.start, |found| found.start)
+                .ok()
+        });
+    }
+
+    /// Every result
+    pub(crate) fn matches(&self) -> &[Range<usize>] {
+        &self.matches
+    }
+
+    /// Is the byte at `offset` part of a result
+    pub(crate) fn is_match(&self, offset: usize) -> bool {
+        let idx = self.matches.partition_point(|m| m.start <= offset);
+        idx > 0 && self.matches[idx - 1].contains(&offset)
+    }
+
+    /// Run the search
+    pub(crate) fn search(&mut self, binary_data: &[u8]) {
+        self.matches.clear();
+        self.current = None;
+        let found = match self.mode {
+            SearchMode::Hex => parse_hex_pattern(&self.query)
+                .map(|pattern| find_pattern(binary_data, &pattern, MAX_RESULTS)),
+            SearchMode::Text => text_regex(&self.query, self.encoding, self.case_insensitive)
+                .map(|regex| find_regex(binary_data, &regex, MAX_RESULTS)),
+            SearchMode::Regex => RegexBuilder::new(&self.query)
+                .unicode(false)
+                .case_insensitive(self.case_insensitive)
+                .build()
+                .map_err(|e| e.to_string())
+                .map(|regex| find_regex(binary_data, &regex, MAX_RESULTS)),
+            SearchMode::Value => value_pattern(&self.query, self.identifier)
+                .map(|pattern| find_pattern(binary_data, &pattern, MAX_RESULTS)),
+        };
+        match found {
+            Ok(matches) => {
+                self.matches = matches;
+                self.error = None;
+            }
+            Err(err) => self.error = Some(err),
+        }
+    }
+
+    /// Select the next result after `position`
+    fn next(&mut self, position: usize) -> Option<Range<usize>> {
+        let idx = self.matches.partition_point(|m| m.start <= position);
+        let idx = if idx < self.matches.len() { idx } else { 0 };
+        self.select(idx)
+    }
+
+    /// Select the previous result before `position`
+    fn previous(&mut self, position: usize) -> Option<Range<usize>> {
+        let idx = self.matches.partition_point(|m| m.start < position);
+        let idx = idx.checked_sub(1).or(self.matches.len().checked_sub(1))?;
+        self.select(idx)
+    }
+
+    /// Bytes of the replacement
+    fn replacement_bytes(&self) -> Result<Vec<u8>, String> {
+        if self.replace_as_hex {
+            return parse_hex_string(&self.replacement);
+        }
+        let encoding = match self.mode {
+            SearchMode::Text => self.encoding,
+            SearchMode::Value => return self.identifier.encode(&self.replacement),
+            SearchMode::Hex | SearchMode::Regex => TextEncoding::Utf8,
+        };
+        let mut bytes = Vec::with_capacity(self.replacement.len());
+        for c in self.replacement.chars() {
+            bytes.extend(encoding.encode(c)?);
+        }
+        Ok(bytes)
+    }
+
+    /// Replace the current result, or the next one after `position`
+    fn replace_one(
+        &mut self,
+        binary_data: &[u8],
+        position: usize,
+    ) -> Result<Option<SearchAction>, String> {
+        let replacement = self.replacement_bytes()?;
+        if self.current.is_none() {
+            self.next(position);
+        }
+        let Some(found) = self.current.and_then(|idx| self.matches.get(idx)).cloned() else {
+            self.replaced = Some(0);
+            return Ok(None);
+        };
+        let edit = Edit::replace(binary_data, std::slice::from_ref(&found), &replacement);
+        self.replaced = Some(1);
+        let select =
+            (!replacement.is_empty()).then(|| found.start..found.start + replacement.len());
+        Ok(Some(SearchAction::Replace { edit, select }))
+    }
+
+    /// Replace every result
+    fn replace_all(&mut self, binary_data: &[u8]) -> Result<Option<SearchAction>, String> {
+        let replacement = self.replacement_bytes()?;
+        let ranges = non_overlapping(&self.matches);
+        self.replaced = Some(ranges.len());
+        if ranges.is_empty() {
+            return Ok(None);
+        }
+        let edit = Edit::replace(binary_data, &ranges, &replacement);
+        Ok(Some(SearchAction::Replace { edit, select: None }))
+    }
+
+    /// Select a result
+    fn select(&mut self, idx: usize) -> Option<Range<usize>> {
+        let found = self.matches.get(idx)?.clone();
+        self.current = Some(idx);
+        Some(found)
+    }
+
+    /// Show the search mode and its options
+    fn ui_mode(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Search:");
+            ui.selectable_value(&mut self.mode, SearchMode::Hex, "Hex");
+            ui.selectable_value(&mut self.mode, SearchMode::Text, "Text");
+            ui.selectable_value(&mut self.mode, SearchMode::Regex, "Regex");
+            ui.selectable_value(&mut self.mode, SearchMode::Value, "Value");
+        });
+        if self.mode == SearchMode::Value {
+            egui::ComboBox::from_id_salt("search_identifier")
+                .selected_text(self.identifier.name())
+                .show_ui(ui, |ui| {
+                    for identifier in Identifier::ALL {
+                        ui.selectable_value(&mut self.identifier, identifier, identifier.name());
+                    }
+                });
+        } else if self.mode != SearchMode::Hex {
+            ui.horizontal(|ui| {
+                if self.mode == SearchMode::Text {
+                    egui::ComboBox::from_id_salt("search_encoding")
+                        .selected_text(self.encoding.name())
+                        .show_ui(ui, |ui| {
+                            for encoding in TextEncoding::ALL {
+                                ui.selectable_value(&mut self.encoding, encoding, encoding.name());
+                            }
+                        });
+                }
+                ui.checkbox(&mut self.case_insensitive, "Ignore case");
+            });
+        }
+    }
+
+    /// Show the replace section
+    fn ui_replace(
+        &mut self,
+        binary_data: &[u8],
+        position: usize,
+        ui: &mut egui::Ui,
+    ) -> Option<SearchAction> {
+        let mut ret = None;
+        ui.collapsing("Replace", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Replace with:");
+                ui.selectable_value(&mut self.replace_as_hex, false, "Text");
+                ui.selectable_value(&mut self.replace_as_hex, true, "Hex");
+            });
+            ui.text_edit_singleline(&mut self.replacement);
+            ui.horizontal(|ui| {
+                let replaced = if ui.button("Replace").clicked() {
+                    Some(self.replace_one(binary_data, position))
+                } else if ui.button("Replace all").clicked() {
+                    Some(self.replace_all(binary_data))
+                } else {
+                    None
+                };
+                match replaced {
+                    Some(Ok(action)) => ret = action,
+                    Some(Err(err)) => self.error = Some(err),
+                    None => {}
+                }
+            });
+            if let Some(nb_replaced) = self.replaced {
+                ui.label(format!("{nb_replaced} replacements made"));
+            }
+        });
+        ret
+    }
+
+    /// Show the search ui
+    pub(crate) fn ui(
+        &mut self,
+        binary_data: &[u8],
+        position: usize,
+        ui: &mut egui::Ui,
+        _error_manager: &mut ErrorManager,
+    ) -> Option<SearchAction> {
+        let mut ret = None;
+        let (next, previous) = ui.ctx().input(|i| {
+            let f3 = i.key_pressed(Key::F3);
+            (f3 && !i.modifiers.shift, f3 && i.modifiers.shift)
+        });
+        if next {
+            ret = self.next(position).map(SearchAction::Select);
+        } else if previous {
+            ret = self.previous(position).map(SearchAction::Select);
+        }
+        if self.is_open {
+            let mut is_open = self.is_open;
+            egui::Window::new("Find")
+                .open(&mut is_open)
+                .show(ui.ctx(), |ui| {
+                    self.ui_mode(ui);
+                    ui.horizontal(|ui| {
+                        let hint = match self.mode {
+                            SearchMode::Hex => {
+                                "Hex bytes, ?? matches any byte, 4? any byte with a high nibble of 4"
+                            }
+                            SearchMode::Text => "Text to find",
+                            SearchMode::Regex => {
+                                "Byte regex, \\xFF matches a byte, (?u) enables Unicode"
+                            }
+                            SearchMode::Value => "GUID, IP or MAC address, or port number",
+                        };
+                        let resp = ui.text_edit_singleline(&mut self.query).on_hover_text(hint);
+                        let enter = resp.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+                        if ui.button("Find").clicked() || enter {
+                            self.search(binary_data);
+                        }
+                    });
+                    if let Some(err) = &self.error {
+                        ui.label(RichText::new(err).color(Color32::LIGHT_RED));
+                    }
+                    ui.horizontal(|ui| {
+                        if self.matches.len() >= MAX_RESULTS {
+                            ui.label(format!("more than {MAX_RESULTS} matches"));
+                        } else {
+                            ui.label(format!("{} matches", self.matches.len()));
+                        }
+                        if ui.button("Previous").on_hover_text("Shift+F3").clicked() {
+                            ret = self.previous(position).map(SearchAction::Select);
+                        }
+                        if ui.button("Next").on_hover_text("F3").clicked() {
+                            ret = self.next(position).map(SearchAction::Select);
+                        }
+                    });
+                    if let Some(action) = self.ui_replace(binary_data, position, ui) {
+                        ret = Some(action);
+                    }
+                    ui.separator();
+                    let row_height = ui.text_style_height(&egui::TextStyle::Button);
+                    ScrollArea::vertical().max_height(300.0).show_rows(
+                        ui,
+                        row_height,
+                        self.matches.len(),
+                        |ui, rows| {
+                            for idx in rows {
+                                let found = &self.matches[idx];
+                                let text = format!(
+                                    "#{idx} {:08X}-{:08X}  {}",
+                                    found.start,
+                                    found.end - 1,
+                                    preview(binary_data, found)
+                                );
+                                if ui
+                                    .selectable_label(self.current == Some(idx), text)
+                                    .clicked()
+                                {
+                                    ret = Some(SearchAction::Select(found.clone()));
+                                    self.current = Some(idx);
+                                }
+                            }
+                        },
+                    );
+                });
+            self.is_open = is_open;
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::{
+        Search, TextEncoding, find_pattern, find_regex, non_overlapping, parse_hex_pattern,
+        preview, text_regex, value_pattern,
+    };
+    use crate::history::Edit;
+    use crate::identifiers::Identifier;
+
+    #[test]
+    fn test_shift_results() {
+        let mut search = Search::new();
+        search.matches = vec![0..2, 4..6, 8..10];
+        search.current = Some(2);
+        let insert = Edit::insert(3, vec![0; 2]);
+        search.shift(&insert, false);
+        assert_eq!(search.matches, vec![0..2, 6..8, 10..12]);
+        assert_eq!(search.current, Some(2));
+        let data = [0; 12];
+        search.shift(&Edit::overwrite(&data, 7, vec![1]), false);
+        assert_eq!(search.matches, vec![0..2, 10..12]);
+        assert_eq!(search.current, Some(1));
+        search.shift(&insert, true);
+        assert_eq!(search.matches, vec![0..2, 8..10]);
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        let pattern = parse_hex_pattern("0x4? ?? \\x41").unwrap();
+        assert_eq!(pattern.len(), 3);
+        assert!(pattern[0].matches(0x4F) && !pattern[0].matches(0x5F));
+        assert!(pattern[1].matches(0x00) && pattern[1].matches(0xFF));
+        assert!(pattern[2].matches(0x41) && !pattern[2].matches(0x42));
+        assert!(parse_hex_pattern("4").is_err());
+        assert!(parse_hex_pattern("").is_err());
+        assert!(parse_hex_pattern("zz").is_err());
+    }
+
+    #[test]
+    fn test_find_pattern() {
+        let data = [0x41, 0x42, 0x41, 0x42, 0x41, 0x00];
+        let pattern = parse_hex_pattern("41 ?2").unwrap();
+        assert_eq!(find_pattern(&data, &pattern, 10), vec![0..2, 2..4]);
+        let pattern = parse_hex_pattern("41 ?? 41").unwrap();
+        assert_eq!(find_pattern(&data, &pattern, 10), vec![0..3, 2..5]);
+        assert_eq!(find_pattern(&data, &pattern, 1), vec![0..3]);
+        let pattern = parse_hex_pattern("?1 00 00").unwrap();
+        assert!(find_pattern(&data, &pattern, 10).is_empty());
+    }
+
+    #[test]
+    fn test_text_search() {
+        let data = b"xxHello hello HELLO";
+        let regex = text_regex("hello", TextEncoding::Ascii, false).unwrap();
+        assert_eq!(find_regex(data, &regex, 10), vec![8..13]);
+        let regex = text_regex("hello", TextEncoding::Utf8, true).unwrap();
+        assert_eq!(find_regex(data, &regex, 10), vec![2..7, 8..13, 14..19]);
+        assert!(text_regex("é", TextEncoding::Ascii, false).is_err());
+    }
+
+    #[test]
+    fn test_text_search_wide() {
+        let data: Vec<u8> = "..Été".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let regex = text_regex("été", TextEncoding::Utf16Le, true).unwrap();
+        assert_eq!(find_regex(&data, &regex, 10), vec![4..10]);
+        let regex = text_regex("été", TextEncoding::Utf16Be, false).unwrap();
+        assert!(find_regex(&data, &regex, 10).is_empty());
+        let data = [0, 0, 0, 0x41, 0, 0, 0, 0x42];
+        let regex = text_regex("ab", TextEncoding::Utf32Be, true).unwrap();
+        assert_eq!(find_regex(&data, &regex, 10), vec![0..8]);
+    }
+
+    #[test]
+    fn test_value_search() {
+        let data = [0xFF, 10, 0, 0, 1, 0x1F, 0x90];
+        let pattern = value_pattern("10.0.0.1", Identifier::Ipv4).unwrap();
+        assert_eq!(find_pattern(&data, &pattern, 10), vec![1..5]);
+        let pattern = value_pattern("8080", Identifier::Port).unwrap();
+        assert_eq!(find_pattern(&data, &pattern, 10), vec![5..7]);
+        assert!(value_pattern("10.0.0", Identifier::Ipv4).is_err());
+    }
+
+    #[test]
+    fn test_preview() {
+        let data = b"0123456789abcdefghij";
+        assert_eq!(preview(data, &(10..12)), "23456789[ab]cdefghij");
+        assert_eq!(preview(data, &(0..1)), "[0]12345678");
+    }
+
+    #[test]
+    fn test_non_overlapping() {
+        let matches = vec![0..3, 2..5, 4..7, 8..9];
+        assert_eq!(non_overlapping(&matches), vec![0..3, 4..7, 8..9]);
+    }
+}
@@ -0,0 +1,213 @@
+//! Byte grouping of the hex column
+
+use crate::bits::sign_extend;
+
+/// Value shown for a group of bytes
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) enum WordFormat {
+    /// hex digits of every byte
+    Hex,
+    /// unsigned integer
+    Unsigned,
+    /// signed integer
+    Signed,
+    /// float, for groups of 4 or 8 bytes
+    Float,
+}
+
+impl WordFormat {
+    /// Every format
+    pub(crate) const ALL: [WordFormat; 4] = [
+        WordFormat::Hex,
+        WordFormat::Unsigned,
+        WordFormat::Signed,
+        WordFormat::Float,
+    ];
+
+    /// Name of the format
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            WordFormat::Hex => "Hex",
+            WordFormat::Unsigned => "Unsigned",
+            WordFormat::Signed => "Signed",
+            WordFormat::Float => "Float",
+        }
+    }
+
+    /// Number of chars of a group of `group_size` bytes
+    fn chars(self, group_size: usize) -> usize {
+        let value_chars = match (self, group_size) {
+            (WordFormat::Unsigned, 1) => 3,
+            (WordFormat::Unsigned, 2) => 5,
+            (WordFormat::Unsigned, 4) => 10,
+            (WordFormat::Signed, 1) => 4,
+            (WordFormat::Signed, 2) => 6,
+            (WordFormat::Signed, 4) => 11,
+            (WordFormat::Unsigned | WordFormat::Signed, _) => 20,
+            (WordFormat::Float, 4) => 13,
+            (WordFormat::Float, 8) => 17,
+            (WordFormat::Hex | WordFormat::Float, _) => 0,
+        };
+        value_chars.max(group_size * 2)
+    }
+
+    /// Text of a group, `None` when the bytes are shown as hex
+    pub(crate) fn format(self, bytes: &[u8], little_endian: bool) -> Option<String> {
+        if bytes.is_empty() || bytes.len() > 8 {
+            return None;
+        }
+        let bits = bytes.len() * 8;
+        let value = if little_endian {
+            bytes
+                .iter()
+                .rev()
+                .fold(0u64, |acc, b| (acc << 8) | u64::from(*b))
+        } else {
+            bytes.iter().fold(0u64, |acc, b| (acc << 8) | u64::from(*b))
+        };
+        match (self, bytes.len()) {
+            (WordFormat::Unsigned, _) => Some(value.to_string()),
+            (WordFormat::Signed, _) => Some(sign_extend(value, bits).to_string()),
+            (WordFormat::Float, 4) => {
+                let float = f32::from_bits(u32::try_from(value).unwrap_or_default());
+                Some(format!("{float:.6e}"))
+            }
+            (WordFormat::Float, 8) => Some(format!("{:.9e}", f64::from_bits(value))),
+            (WordFormat::Hex | WordFormat::Float, _) => None,
+        }
+    }
+}
+
+/// Layout of the hex column
+#[derive(Debug)]
+pub(crate) struct HexLayout {
+    /// bytes per group
+    group_size: usize,
+    /// bytes of a group are shown in little endian order
+    little_endian: bool,
+    /// chars of a group
+    group_chars: usize,
+    /// width of a char
+    char_width: f32,
+}
+
+impl HexLayout {
+    /// New layout
+    pub(crate) fn new(
+        group_size: usize,
+        little_endian: bool,
+        format: WordFormat,
+        char_width: f32,
+    ) -> Self {
+        let group_size = group_size.max(1);
+        Self {
+            group_size,
+            little_endian,
+            group_chars: format.chars(group_size),
+            char_width,
+        }
+    }
+
+    /// Layout of the bit view, each byte shown as 8 bits
+    pub(crate) fn bits(char_width: f32) -> Self {
+        Self {
+            group_size: 1,
+            little_endian: false,
+            group_chars: 8,
+            char_width,
+        }
+    }
+
+    /// Bytes per group
+    pub(crate) fn group_size(&self) -> usize {
+        self.group_size
+    }
+
+    /// Width of a group, followed by a one char gap
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn group_width(&self) -> f32 {
+        self.group_chars as f32 * self.char_width
+    }
+
+    /// Width of the cell of a byte
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn byte_width(&self) -> f32 {
+        self.group_width() / self.group_size as f32
+    }
+
+    /// Position of a group from the start of the column
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn group_x(&self, group: usize) -> f32 {
+        group as f32 * (self.group_width() + self.char_width)
+    }
+
+    /// Position of the byte `idx` of a line of `line_len` bytes from the start of the column
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn byte_x(&self, idx: usize, line_len: usize) -> f32 {
+        let group = idx / self.group_size;
+        let in_group = idx % self.group_size;
+        let group_len = line_len
+            .saturating_sub(group * self.group_size)
+            .clamp(1, self.group_size);
+        let position = if self.little_endian {
+            group_len.saturating_sub(in_group + 1)
+        } else {
+            in_group
+        };
+        self.group_x(group) + position as f32 * self.byte_width()
+    }
+
+    /// Width of the column for lines of `bytes_per_line` bytes
+    pub(crate) fn width(&self, bytes_per_line: usize) -> f32 {
+        self.group_x(bytes_per_line.div_ceil(self.group_size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HexLayout, WordFormat};
+
+    #[test]
+    fn test_format_words() {
+        let bytes = [0x01, 0x02, 0x03, 0x04];
+        assert_eq!(
+            WordFormat::Unsigned.format(&bytes, false).as_deref(),
+            Some("16909060")
+        );
+        assert_eq!(
+            WordFormat::Unsigned.format(&bytes, true).as_deref(),
+            Some("67305985")
+        );
+        assert_eq!(
+            WordFormat::Signed.format(&[0xFF, 0xFE], true).as_deref(),
+            Some("-257")
+        );
+        assert_eq!(
+            WordFormat::Signed.format(&[0x7F], true).as_deref(),
+            Some("127")
+        );
+        assert_eq!(
+            WordFormat::Float
+                .format(&[0x3F, 0x80, 0, 0], false)
+                .as_deref(),
+            Some("1.000000e0")
+        );
+        assert_eq!(WordFormat::Float.format(&[0x3F, 0x80], false), None);
+        assert_eq!(WordFormat::Hex.format(&bytes, false), None);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_layout_little_endian() {
+        let layout = HexLayout::new(4, true, WordFormat::Hex, 1.0);
+        // groups of 8 chars and a gap
+        assert_eq!(layout.byte_x(0, 6), 6.0);
+        assert_eq!(layout.byte_x(3, 6), 0.0);
+        // partial group at the end of the line
+        assert_eq!(layout.byte_x(4, 6), 11.0);
+        assert_eq!(layout.byte_x(5, 6), 9.0);
+        assert_eq!(layout.width(6), 18.0);
+        let layout = HexLayout::new(2, false, WordFormat::Hex, 1.0);
+        assert_eq!(layout.byte_x(3, 4), 7.0);
+    }
+}